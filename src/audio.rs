@@ -0,0 +1,115 @@
+//! Splitting on a sound from the game, for games without an autosplitter.
+//! Samples come from a recording command's stdout as raw signed 16-bit little
+//! endian mono, so any capture tool works, e.g. `parec` or `arecord`.
+
+use std::{
+    io::Read,
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioCue {
+    /// Records from the input device to stdout until it's killed.
+    pub command: String,
+    /// Has to match what the command records at.
+    pub sample_rate: u32,
+    /// How loud the cue is, from 0 to 1.
+    pub threshold: f32,
+    /// How long the sound has to stay above the threshold, in milliseconds.
+    pub min_duration_ms: u64,
+    /// Ignore further cues for this long after one, in milliseconds.
+    pub cooldown_ms: u64,
+}
+
+impl Default for AudioCue {
+    fn default() -> Self {
+        Self {
+            command: String::from("parec --raw --format=s16le --channels=1 --rate=8000"),
+            sample_rate: 8000,
+            threshold: 0.5,
+            min_duration_ms: 100,
+            cooldown_ms: 3000,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AudioEvent {
+    Cue,
+    /// Listening stopped, e.g. because the device isn't there.
+    Failed(String),
+}
+
+/// Starts listening on a background thread. The thread stops the recording
+/// and exits once the receiver is dropped.
+pub fn listen(cue: AudioCue) -> Receiver<AudioEvent> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut args = cue.command.split_whitespace();
+        let Some(program) = args.next() else {
+            sender
+                .send(AudioEvent::Failed(String::from("no audio command set")))
+                .ok();
+            return;
+        };
+        let mut child = match Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(err) => {
+                sender
+                    .send(AudioEvent::Failed(format!(
+                        "couldn't start {program}: {err}"
+                    )))
+                    .ok();
+                return;
+            }
+        };
+        let mut stdout = child.stdout.take().unwrap();
+        // loudness is measured over 10ms windows
+        let window = (cue.sample_rate as usize / 100).max(1);
+        let needed = (cue.min_duration_ms / 10).max(1);
+        let cooldown = Duration::from_millis(cue.cooldown_ms);
+        let mut buf = vec![0u8; window * 2];
+        let mut loud_windows = 0;
+        let mut last_cue: Option<Instant> = None;
+        let failure = loop {
+            if let Err(err) = stdout.read_exact(&mut buf) {
+                break format!("audio capture stopped: {err}");
+            }
+            let sum: f64 = buf
+                .chunks_exact(2)
+                .map(|sample| {
+                    let sample = i16::from_le_bytes([sample[0], sample[1]]) as f64 / 32768.0;
+                    sample * sample
+                })
+                .sum();
+            let rms = (sum / window as f64).sqrt();
+            loud_windows = match rms >= cue.threshold as f64 {
+                true => loud_windows + 1,
+                false => 0,
+            };
+            if loud_windows == needed && last_cue.is_none_or(|last| last.elapsed() >= cooldown) {
+                last_cue = Some(Instant::now());
+                if sender.send(AudioEvent::Cue).is_err() {
+                    child.kill().ok();
+                    child.wait().ok();
+                    return;
+                }
+            }
+        };
+        child.kill().ok();
+        child.wait().ok();
+        sender.send(AudioEvent::Failed(failure)).ok();
+    });
+    receiver
+}
@@ -0,0 +1,65 @@
+//! Clock digits drawn out of block characters, big enough to read from across the room.
+
+const HEIGHT: usize = 5;
+
+/// Each glyph as rows of `#` and spaces. Characters without one are left out.
+fn glyph(c: char) -> Option<[&'static str; HEIGHT]> {
+    Some(match c {
+        '0' => ["###", "# #", "# #", "# #", "###"],
+        '1' => ["  #", "  #", "  #", "  #", "  #"],
+        '2' => ["###", "  #", "###", "#  ", "###"],
+        '3' => ["###", "  #", "###", "  #", "###"],
+        '4' => ["# #", "# #", "###", "  #", "  #"],
+        '5' => ["###", "#  ", "###", "  #", "###"],
+        '6' => ["###", "#  ", "###", "# #", "###"],
+        '7' => ["###", "  #", "  #", "  #", "  #"],
+        '8' => ["###", "# #", "###", "# #", "###"],
+        '9' => ["###", "# #", "###", "  #", "###"],
+        ':' => [" ", "#", " ", "#", " "],
+        '.' => [" ", " ", " ", " ", "#"],
+        '-' => ["   ", "   ", "###", "   ", "   "],
+        '+' => ["   ", " # ", "###", " # ", "   "],
+        ' ' => [" ", " ", " ", " ", " "],
+        _ => return None,
+    })
+}
+
+/// The width and height `text` takes up at `scale`.
+pub fn size(text: &str, scale: usize) -> (usize, usize) {
+    let glyphs: Vec<_> = text.chars().filter_map(glyph).collect();
+    let width: usize = glyphs.iter().map(|glyph| glyph[0].len()).sum();
+    let gaps = glyphs.len().saturating_sub(1);
+    ((width + gaps) * scale * 2, HEIGHT * scale)
+}
+
+/// The largest scale at which `text` fits in `width` by `height`, at least 1.
+pub fn fit(text: &str, width: usize, height: usize) -> usize {
+    (2..)
+        .take_while(|&scale| {
+            let (w, h) = size(text, scale);
+            w <= width && h <= height
+        })
+        .last()
+        .unwrap_or(1)
+}
+
+/// Renders `text` into lines of block characters. Every pixel is two columns
+/// wide so the digits keep their shape in cells that are taller than they're wide.
+pub fn lines(text: &str, scale: usize) -> Vec<String> {
+    let glyphs: Vec<_> = text.chars().filter_map(glyph).collect();
+    let mut lines = Vec::with_capacity(HEIGHT * scale);
+    for row in 0..HEIGHT {
+        let mut line = String::new();
+        for (i, glyph) in glyphs.iter().enumerate() {
+            if i > 0 {
+                line.push_str(&" ".repeat(scale * 2));
+            }
+            for pixel in glyph[row].chars() {
+                let cell = if pixel == '#' { "█" } else { " " };
+                line.push_str(&cell.repeat(scale * 2));
+            }
+        }
+        lines.resize(lines.len() + scale, line);
+    }
+    lines
+}
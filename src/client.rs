@@ -0,0 +1,135 @@
+//! A display for a timer running elsewhere, e.g. `shplit --daemon`, kept up to
+//! date by polling its server.
+
+use std::{
+    error::Error,
+    io::{self, BufRead, BufReader, Write},
+    net::TcpStream,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    event::{self, Event, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use tui::{prelude::*, widgets::*};
+
+use crate::{bigtext, config::Config, keybinds::Action, ui::STATUS_DURATION, watch_exit_signals};
+
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    fn send(&mut self, command: &str) -> io::Result<()> {
+        writeln!(self.writer, "{command}")
+    }
+
+    fn ask(&mut self, command: &str) -> io::Result<String> {
+        self.send(command)?;
+        let mut reply = String::new();
+        self.reader.read_line(&mut reply)?;
+        Ok(reply.trim().to_string())
+    }
+}
+
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    let stream = TcpStream::connect(&config.server_address)
+        .map_err(|err| format!("couldn't connect to {}: {err}", config.server_address))?;
+    let mut connection = Connection {
+        reader: BufReader::new(stream.try_clone()?),
+        writer: stream,
+    };
+
+    let terminate = Arc::new(AtomicBool::new(false));
+    watch_exit_signals(&terminate);
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    let res = run_client(&mut terminal, &mut connection, &config, &terminate);
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    res
+}
+
+fn run_client<B: Backend>(
+    terminal: &mut Terminal<B>,
+    connection: &mut Connection,
+    config: &Config,
+    terminate: &AtomicBool,
+) -> Result<(), Box<dyn Error>> {
+    let mut pending_keys = Vec::new();
+    let mut status: Option<(&str, Instant)> = None;
+    loop {
+        if terminate.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let time = connection.ask("getcurrenttime")?;
+        let split = connection.ask("getcurrentsplitname")?;
+        let phase = connection.ask("getcurrenttimerphase")?;
+        terminal.draw(|f| {
+            let area = f.size();
+            let scale = bigtext::fit(
+                &time,
+                area.width as usize,
+                (area.height as usize).saturating_sub(2),
+            );
+            let mut lines: Vec<Line> = bigtext::lines(&time, scale)
+                .into_iter()
+                .map(Line::from)
+                .collect();
+            let info = match status {
+                Some((status, since)) if since.elapsed() < STATUS_DURATION => status.to_string(),
+                _ => format!("{split} ({phase})"),
+            };
+            lines.extend([Line::default(), Line::from(info)]);
+            let top = area.height.saturating_sub(lines.len() as u16) / 2;
+            f.render_widget(
+                Paragraph::new(lines).alignment(Alignment::Center),
+                Rect {
+                    y: area.y + top,
+                    height: area.height - top,
+                    ..area
+                },
+            );
+        })?;
+        if !event::poll(Duration::from_secs_f32(1.0 / 30.0))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let Some(action) = config.keybindings().press(&mut pending_keys, key) else {
+            continue;
+        };
+        match action {
+            Action::Quit => return Ok(()),
+            Action::Redraw => terminal.clear()?,
+            Action::Split => connection.send("startorsplit")?,
+            Action::Rewind => {
+                for _ in 0..config.rewind_splits {
+                    connection.send("unsplit")?;
+                }
+            }
+            // the server can only split, which would end the run early rather than
+            // wait for the last split like the stop key does locally
+            Action::Stop => {
+                status = Some((
+                    "stopping isn't available when attached, split instead",
+                    Instant::now(),
+                ))
+            }
+            _ => {}
+        }
+    }
+}
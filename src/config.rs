@@ -1,41 +1,420 @@
-use std::{io::Write, path::PathBuf};
+use std::{
+    borrow::Cow, collections::BTreeMap, io::Write, path::PathBuf, sync::OnceLock, time::Duration,
+};
 
+use livesplit::{HotkeyConfig, TimeSpan, TimingMethod};
 use serde::{Deserialize, Serialize};
 
-pub fn config_path() -> PathBuf {
+use crate::{
+    keybinds::Keybindings,
+    theme::Theme,
+    timefmt::{parse_signed_time, Rounding},
+};
+
+/// The user's config directory, if the system has one.
+pub fn config_path() -> Option<PathBuf> {
     directories::ProjectDirs::from("org", "shplit", "shplit")
         .map(|proj_dirs| proj_dirs.config_dir().to_path_buf())
-        .expect("Can't locate user config directory.")
+}
+
+/// Whether settings can be written to the config directory. When they can't,
+/// shplit still runs, but whatever changes only lasts until it quits.
+pub fn config_writable() -> bool {
+    let Some(dir) = config_path() else {
+        return false;
+    };
+    let probe = dir.join(".shplit-write-test");
+    let writable = std::fs::create_dir_all(&dir).is_ok() && std::fs::File::create(&probe).is_ok();
+    std::fs::remove_file(probe).ok();
+    writable
+}
+
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Namespaces the config file so separate setups don't share one config.
+/// Must be called before anything is loaded; later calls are ignored.
+pub fn set_profile(name: String) {
+    PROFILE.set(name).ok();
+}
+
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get().map(String::as_str)
+}
+
+/// A file in the config directory that belongs to the profile, e.g. `config-stream.toml`
+/// for `config` with `--profile stream`.
+fn profile_file(name: &str) -> Option<PathBuf> {
+    let dir = config_path()?;
+    match profile() {
+        Some(profile) => Some(dir.join(format!("{name}-{profile}.toml"))),
+        None => Some(dir.join(format!("{name}.toml"))),
+    }
 }
 
 pub trait TomlConfig: Serialize + for<'a> Deserialize<'a> {
-    fn path() -> PathBuf;
+    fn path() -> Option<PathBuf>;
     fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        let path = Self::path();
-        println!("{:?}", &path);
+        let path = Self::path().ok_or("no config directory")?;
         let this: Self = toml::from_str(&std::fs::read_to_string(path)?)?;
         Ok(this)
     }
     fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        std::fs::create_dir_all(Self::path().parent().unwrap())?;
-        std::fs::File::create(Self::path())?.write_all(toml::to_string_pretty(self)?.as_bytes())?;
+        let path = Self::path().ok_or("no config directory")?;
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::File::create(path)?.write_all(toml::to_string_pretty(self)?.as_bytes())?;
         Ok(())
     }
 }
 
+/// Which rows of the table get a delta.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeltaScope {
+    All,
+    /// Only the final split, i.e. the total.
+    Final,
+}
+
+/// What the time column shows for the segment in progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurrentRow {
+    /// Nothing until the split happens.
+    Empty,
+    /// The running total.
+    Total,
+    /// How long the segment has been going.
+    Segment,
+    /// The comparison's split time, i.e. what to beat.
+    Comparison,
+}
+
+/// A column of the splits table after the segment names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Delta,
+    /// The split time, or the running total in the current row.
+    Time,
+    /// The current comparison's split time.
+    Comparison,
+    /// The segment's average time over the attempt history, weighted towards recent attempts.
+    Average,
+    /// The segment's median time over the attempt history, weighted towards recent attempts.
+    Median,
+}
+
+impl Column {
+    /// The comparison a statistic column reads its segment times from.
+    pub fn statistic(self) -> Option<&'static str> {
+        match self {
+            Self::Average => Some(livesplit::comparison::average_segments::NAME),
+            Self::Median => Some(livesplit::comparison::median_segments::NAME),
+            _ => None,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Delta => "Delta",
+            Self::Time => "Time",
+            Self::Comparison => "Comparison",
+            Self::Average => "Average",
+            Self::Median => "Median",
+        }
+    }
+
+    pub fn width(self) -> tui::layout::Constraint {
+        match self {
+            Self::Delta | Self::Comparison | Self::Average | Self::Median => {
+                tui::layout::Constraint::Length(10)
+            }
+            Self::Time => tui::layout::Constraint::Min(5),
+        }
+    }
+}
+
+/// A named group of consecutive segments, shown with a header row and its own totals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Section {
+    pub name: String,
+    /// Index of the first segment, counting from 0.
+    pub start: usize,
+    /// Index of the last segment, inclusive.
+    pub end: usize,
+}
+
+impl Section {
+    pub fn contains(&self, segment: usize) -> bool {
+        (self.start..=self.end).contains(&segment)
+    }
+}
+
+/// A timing method and comparison switched to together with the preset key,
+/// e.g. `{ name = "IGT vs WR", timing_method = "GameTime", comparison = "World Record" }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    /// Left as it is when unset.
+    pub timing_method: Option<TimingMethod>,
+    pub comparison: Option<String>,
+}
+
+/// Scrolling of the current split's name when it's too long for its column.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Marquee {
+    /// Characters per second, 0 to cut the name off instead.
+    pub speed: f64,
+    /// Hold still for a moment at the start and end of the name.
+    pub pause_at_ends: bool,
+}
+
+impl Default for Marquee {
+    fn default() -> Self {
+        Self {
+            speed: 4.0,
+            pause_at_ends: true,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub split_file: Option<String>,
+    /// Where the splits files are kept.
+    pub splits_dir: Option<String>,
+    /// On launch, open whichever `.lss` in `splits_dir` was changed last instead of `split_file`.
+    pub open_latest: bool,
+    /// More places for the library to look for splits files, as directories or
+    /// globs like `~/speedruns/**/*.lss`.
+    pub library_paths: Vec<String>,
+    /// How many directories deep the library looks.
+    pub library_depth: usize,
+    /// How many splits the rewind key undoes at once.
+    pub rewind_splits: usize,
+    /// What the current split's row shows in the time column while it's in progress.
+    pub current_row: CurrentRow,
+    /// Before a run starts, fill the time column with the comparison's split times.
+    pub preview_comparison: bool,
+    /// Write the splits file back to disk as soon as a run is finished.
+    pub autosave: bool,
+    /// Save an attempt in progress when the system closes shplit, e.g. with its
+    /// window or on logout, rather than only restoring the terminal.
+    pub save_on_signal: bool,
+    /// Seconds a segment has to run before splitting ends it, to catch an accidental
+    /// double press between close splits. 0 to disable.
+    pub min_segment_secs: f64,
+    /// Leave the clock running after the last split until the stop key is pressed.
+    pub keep_running_after_last_split: bool,
+    /// Note the time of day of every split in the info panel, for lining splits up with a VOD.
+    pub split_time_of_day: bool,
+    /// Seconds to show the just-finished segment's time in its row after splitting, 0 to disable.
+    pub split_result_secs: f64,
+    /// Time the table in this method no matter what the clock uses.
+    pub table_timing_method: Option<TimingMethod>,
+    /// The table's columns after the segment names, in order.
+    pub columns: Vec<Column>,
+    /// Leave the delta column out, flipped with the toggle key.
+    pub hide_delta: bool,
+    /// Leave out the table's header row and the gap under it, for two more rows of splits.
+    pub hide_header: bool,
+    pub delta_scope: DeltaScope,
+    /// Show the comparison's split time in the delta column until the split happens.
+    pub delta_fallback: bool,
+    pub rounding: Rounding,
+    /// Only show whole seconds on the clock while it's running, to keep it from flickering.
+    pub coarse_running_clock: bool,
+    /// Show the current segment's gold in the footer, highlighted while it can still be beaten.
+    pub current_gold: bool,
+    /// Name the segment that's lost the most time against the comparison so far in the footer.
+    pub biggest_loss: bool,
+    /// Show the sum of best in the footer.
+    pub sum_of_best: bool,
+    /// Segments, by name, whose golds the sum of best leaves out for being out of reach
+    /// most of the time, like a trick that's rarely landed. Their average counts instead.
+    pub unreliable_segments: Vec<String>,
+    /// Target time shown in the footer, for runs that don't have a "Goal" comparison.
+    pub goal_time: Option<String>,
+    /// How often the screen is redrawn, clamped to 1-240. 0 redraws as fast as the
+    /// terminal takes it, for the smoothest clock at the cost of a busy CPU core.
+    pub fps: f64,
+    /// Take over the whole terminal. When off, shplit renders inline below the prompt.
+    pub alternate_screen: bool,
+    /// Clear and redraw the whole screen this often, in seconds, for terminals where
+    /// other output scrolls the display out of place. 0 to only redraw what changes.
+    pub redraw_secs: f64,
+    /// Height of the inline region, fitting the loaded run if unset.
+    pub inline_height: Option<u16>,
+    /// In a terminal smaller than this, shplit asks for a bigger one instead of drawing.
+    pub min_width: u16,
+    pub min_height: u16,
+    /// Moving the highlight past the last segment with the arrow keys goes back to the first.
+    pub wrap_navigation: bool,
+    /// List the splits from the last one up, e.g. for an overlay that reads bottom to top.
+    pub reverse_order: bool,
+    /// Rows the table moves per notch of the mouse wheel, 0 to leave the wheel alone.
+    pub scroll_step: usize,
+    /// The split key on its own, e.g. `split_key = "s"`, so changing it doesn't
+    /// take a whole `[keybindings]` table. Only used while `keybindings.split` is left
+    /// at its default, a binding set there is more specific.
+    pub split_key: Option<String>,
+    pub keybindings: Keybindings,
+    pub theme: Theme,
+    pub sections: Vec<Section>,
+    pub marquee: Marquee,
+    /// Replaces the hint shown when no splits file is loaded.
+    pub splash_hint: Option<String>,
+    /// Added to a comparison's times when working out deltas against it, keyed by the
+    /// comparison's name, e.g. `"Personal Best" = "+2"` for splits timed from a later start.
+    pub comparison_offsets: BTreeMap<String, String>,
+    /// Comparisons to show the live delta against all at once, in order, above the footer.
+    pub reference_deltas: Vec<String>,
+    /// Cycled through in order with the preset key.
+    pub presets: Vec<Preset>,
+    /// Where `--daemon` listens for LiveSplit server commands and display clients.
+    pub server_address: String,
+    /// Global hotkeys used by `--daemon`, in LiveSplit's key names.
+    pub daemon_hotkeys: HotkeyConfig,
+    /// Split when the game plays a sound, if set.
+    #[cfg(feature = "audio-cue")]
+    pub audio_cue: Option<crate::audio::AudioCue>,
 }
 
+/// Used when the configured `fps` isn't a number.
+const DEFAULT_FPS: f64 = 30.0;
+
 impl Default for Config {
     fn default() -> Self {
-        Self { split_file: None }
+        Self {
+            split_file: None,
+            splits_dir: None,
+            open_latest: false,
+            library_paths: Vec::new(),
+            library_depth: 4,
+            rewind_splits: 1,
+            current_row: CurrentRow::Empty,
+            preview_comparison: false,
+            autosave: false,
+            save_on_signal: false,
+            min_segment_secs: 0.0,
+            keep_running_after_last_split: false,
+            split_time_of_day: false,
+            split_result_secs: 2.0,
+            table_timing_method: None,
+            columns: vec![Column::Delta, Column::Time],
+            hide_delta: false,
+            hide_header: false,
+            delta_scope: DeltaScope::All,
+            delta_fallback: false,
+            rounding: Rounding::Truncate,
+            coarse_running_clock: false,
+            current_gold: false,
+            biggest_loss: false,
+            sum_of_best: false,
+            unreliable_segments: Vec::new(),
+            goal_time: None,
+            fps: DEFAULT_FPS,
+            alternate_screen: true,
+            redraw_secs: 0.0,
+            inline_height: None,
+            min_width: 30,
+            min_height: 7,
+            wrap_navigation: false,
+            reverse_order: false,
+            scroll_step: 3,
+            split_key: None,
+            keybindings: Keybindings::default(),
+            theme: Theme::default(),
+            sections: Vec::new(),
+            marquee: Marquee::default(),
+            splash_hint: None,
+            comparison_offsets: BTreeMap::new(),
+            reference_deltas: Vec::new(),
+            presets: Vec::new(),
+            server_address: String::from("127.0.0.1:16834"),
+            daemon_hotkeys: HotkeyConfig::default(),
+            #[cfg(feature = "audio-cue")]
+            audio_cue: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn frame_time(&self) -> Duration {
+        if self.fps == 0.0 {
+            return Duration::ZERO;
+        }
+        let fps = if self.fps.is_finite() {
+            self.fps
+        } else {
+            DEFAULT_FPS
+        };
+        Duration::from_secs_f64(1.0 / fps.clamp(1.0, 240.0))
+    }
+
+    /// The keybindings in effect, with `split_key` in place of a default split binding.
+    pub fn keybindings(&self) -> Cow<'_, Keybindings> {
+        match &self.split_key {
+            Some(split) if self.keybindings.split == Keybindings::default().split => {
+                Cow::Owned(Keybindings {
+                    split: split.clone(),
+                    ..self.keybindings.clone()
+                })
+            }
+            _ => Cow::Borrowed(&self.keybindings),
+        }
+    }
+
+    /// The columns the table shows right now.
+    pub fn table_columns(&self) -> impl Iterator<Item = Column> + '_ {
+        self.columns
+            .iter()
+            .copied()
+            .filter(|&column| !(self.hide_delta && column == Column::Delta))
+    }
+
+    /// The configured offset for a comparison, zero if there's none or it doesn't parse.
+    pub fn comparison_offset(&self, comparison: &str) -> TimeSpan {
+        self.comparison_offsets
+            .get(comparison)
+            .and_then(|offset| parse_signed_time(offset))
+            .unwrap_or_else(TimeSpan::zero)
     }
 }
 
 impl TomlConfig for Config {
-    fn path() -> PathBuf {
-        config_path().join("config.toml")
+    fn path() -> Option<PathBuf> {
+        profile_file("config")
+    }
+}
+
+/// What shplit remembers about a single splits file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileState {
+    pub comparison: Option<String>,
+    pub timing_method: Option<TimingMethod>,
+}
+
+/// Per-file state, keyed by the splits file's path. Each profile keeps its own.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FileStates(BTreeMap<String, FileState>);
+
+impl FileStates {
+    pub fn get(&self, split_file: &str) -> FileState {
+        self.0.get(split_file).cloned().unwrap_or_default()
+    }
+
+    pub fn get_mut(&mut self, split_file: &str) -> &mut FileState {
+        self.0.entry(split_file.to_string()).or_default()
+    }
+}
+
+impl TomlConfig for FileStates {
+    fn path() -> Option<PathBuf> {
+        profile_file("files")
     }
 }
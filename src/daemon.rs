@@ -0,0 +1,59 @@
+//! Running without a terminal: global hotkeys drive the timer and display
+//! clients follow along through the server.
+
+use std::{error::Error, path::Path, thread, time::Duration};
+
+use livesplit::HotkeySystem;
+
+use crate::{config::Config, load, modified_time, server, write_splits};
+
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    let Some(ref split_file) = config.split_file else {
+        return Err("no splits file set, open one in shplit first".into());
+    };
+    let mut mtime = modified_time(Path::new(split_file));
+    let run = load::parse_run(&std::fs::read(split_file)?, Some(Path::new(split_file)))?.run;
+    let timer = livesplit::Timer::new(run)
+        .map_err(|_| "the splits file has no segments")?
+        .into_shared();
+    // dropping the hotkey system unregisters the hotkeys, so it has to live as long as the daemon
+    let _hotkeys = match HotkeySystem::with_config(timer.clone(), config.daemon_hotkeys) {
+        Ok(hotkeys) => Some(hotkeys),
+        Err(err) => {
+            eprintln!("couldn't register the hotkeys ({err:?}), only server commands will work");
+            None
+        }
+    };
+    server::spawn(timer.clone(), &config.server_address)
+        .map_err(|err| format!("couldn't listen on {}: {err}", config.server_address))?;
+    println!(
+        "timing {split_file}, display with `shplit --attach` (listening on {})",
+        config.server_address
+    );
+
+    let mut attempts = timer.read().unwrap().run().attempt_history().len();
+    loop {
+        thread::sleep(Duration::from_millis(100));
+        let timer = timer.read().unwrap();
+        // a reset records the attempt, which is when autosave writes it out
+        if timer.run().attempt_history().len() != attempts {
+            attempts = timer.run().attempt_history().len();
+            if config.autosave {
+                // the timer is all there is, so it keeps going even if the file can't be written
+                if let Err(err) = save(&timer, split_file, &mut mtime) {
+                    eprintln!("couldn't save {split_file}: {err}");
+                }
+            }
+        }
+    }
+}
+
+fn save(
+    timer: &livesplit::Timer,
+    split_file: &str,
+    mtime: &mut Option<std::time::SystemTime>,
+) -> Result<(), Box<dyn Error>> {
+    let mut lss = String::new();
+    livesplit::run::saver::livesplit::save_timer(timer, &mut lss)?;
+    write_splits(Path::new(split_file), &lss, mtime)
+}
@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+
+use livesplit::{AtomicDateTime, Time, TimeSpan, TimingMethod};
+use serde::{Deserialize, Serialize};
+use time::UtcOffset;
+
+use crate::sidecar::Sidecar;
+
+/// Attempts started and the best finished time in each timing method on one day.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Day {
+    pub attempts: u32,
+    /// In seconds, like the times in [`crate::golds::Gold`].
+    pub real_time: Option<f64>,
+    pub game_time: Option<f64>,
+}
+
+impl Day {
+    pub fn best(&self, method: TimingMethod) -> Option<TimeSpan> {
+        match method {
+            TimingMethod::RealTime => self.real_time,
+            TimingMethod::GameTime => self.game_time,
+        }
+        .map(TimeSpan::from_seconds)
+    }
+}
+
+/// Per-day practice stats keyed by local date, in a sidecar next to the splits file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DailyStats(BTreeMap<String, Day>);
+
+impl Sidecar for DailyStats {
+    const EXTENSION: &'static str = "daily.toml";
+}
+
+impl DailyStats {
+    fn today_key(offset: UtcOffset) -> String {
+        AtomicDateTime::now()
+            .time
+            .to_offset(offset)
+            .date()
+            .to_string()
+    }
+
+    pub fn today(&self, offset: UtcOffset) -> Day {
+        self.0
+            .get(&Self::today_key(offset))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn record_attempt(&mut self, offset: UtcOffset) {
+        self.0.entry(Self::today_key(offset)).or_default().attempts += 1;
+    }
+
+    pub fn record_finish(&mut self, time: Time, offset: UtcOffset) {
+        let day = self.0.entry(Self::today_key(offset)).or_default();
+        for (best, time) in [
+            (&mut day.real_time, time.real_time),
+            (&mut day.game_time, time.game_time),
+        ] {
+            let Some(seconds) = time.map(|time| time.total_seconds()) else {
+                continue;
+            };
+            if best.is_none_or(|best| seconds < best) {
+                *best = Some(seconds);
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    error::Error,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use livesplit::{Run, Time, TimeSpan, TimingMethod};
+use serde::{Deserialize, Serialize};
+
+/// The comparison shared golds are imported as.
+pub const COMPARISON: &str = "Shared Golds";
+
+/// A run's best segments on their own, for passing around a route's golds
+/// without the rest of the splits file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SharedGolds {
+    pub game: String,
+    pub category: String,
+    pub segments: Vec<Gold>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Gold {
+    pub name: String,
+    /// In seconds, since `TimeSpan` can't be written back out.
+    pub real_time: Option<f64>,
+    pub game_time: Option<f64>,
+}
+
+impl SharedGolds {
+    pub fn path_for(split_file: &Path) -> PathBuf {
+        split_file.with_extension("golds.toml")
+    }
+
+    pub fn from_run(run: &Run) -> Self {
+        let seconds = |time: Option<TimeSpan>| time.map(|time| time.total_seconds());
+        Self {
+            game: run.game_name().to_string(),
+            category: run.category_name().to_string(),
+            segments: run
+                .segments()
+                .iter()
+                .map(|segment| Gold {
+                    name: segment.name().to_string(),
+                    real_time: seconds(segment.best_segment_time().real_time),
+                    game_time: seconds(segment.best_segment_time().game_time),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        std::fs::File::create(path)?.write_all(toml::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// Adds the golds to `run` as the sum of best comparison they'd make, replacing
+    /// an earlier import. Segments are matched up in order, so the segment count has
+    /// to agree. Returns how many segment names didn't match, in case the route differs.
+    pub fn add_to(&self, run: &mut Run) -> Result<usize, String> {
+        if self.segments.len() != run.len() {
+            return Err(format!(
+                "the golds are for {} segments, this run has {}",
+                self.segments.len(),
+                run.len()
+            ));
+        }
+        if !run.comparisons().any(|comparison| comparison == COMPARISON) {
+            run.add_custom_comparison(COMPARISON)
+                .map_err(|_| format!("can't add a comparison called {COMPARISON:?}"))?;
+        }
+        let mut total = [Some(TimeSpan::zero()); 2];
+        let mut mismatched = 0;
+        for (segment, gold) in run.segments_mut().iter_mut().zip(&self.segments) {
+            if segment.name() != gold.name {
+                mismatched += 1;
+            }
+            let golds = [gold.real_time, gold.game_time];
+            for (total, gold) in total.iter_mut().zip(golds) {
+                *total = total
+                    .zip(gold)
+                    .map(|(total, gold)| total + TimeSpan::from_seconds(gold));
+            }
+            *segment.comparison_mut(COMPARISON) = Time::new()
+                .with_timing_method(TimingMethod::RealTime, total[0])
+                .with_timing_method(TimingMethod::GameTime, total[1]);
+        }
+        Ok(mismatched)
+    }
+}
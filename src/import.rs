@@ -0,0 +1,107 @@
+//! Bringing settings over from LiveSplit.
+
+use crate::keybinds::Keybindings;
+
+/// Copies the hotkeys from LiveSplit's `settings.cfg` into `keybindings`, for the
+/// actions shplit has. Keys a terminal can't tell apart, like the number pad,
+/// become their plain counterparts. Returns the names of the bindings it set.
+pub fn livesplit_hotkeys(settings: &str, keybindings: &mut Keybindings) -> Vec<&'static str> {
+    // newer versions keep several profiles, the first one is what LiveSplit starts with
+    let settings = match settings.find("<HotkeyProfile ") {
+        Some(start) => &settings[start..],
+        None => settings,
+    };
+    let mut imported = Vec::new();
+    let targets: [(&str, &'static str, &mut String); 4] = [
+        ("SplitKey", "split", &mut keybindings.split),
+        ("UndoKey", "rewind", &mut keybindings.rewind),
+        (
+            "SwitchComparisonNext",
+            "next_comparison",
+            &mut keybindings.next_comparison,
+        ),
+        (
+            "SwitchComparisonPrevious",
+            "previous_comparison",
+            &mut keybindings.previous_comparison,
+        ),
+    ];
+    for (tag, name, binding) in targets {
+        if let Some(key) = element(settings, tag).and_then(convert_key) {
+            *binding = key;
+            imported.push(name);
+        }
+    }
+    imported
+}
+
+/// The text of the first `<tag>text</tag>`, which is all of the XML the hotkeys need.
+fn element<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let end = xml[start..].find(&format!("</{tag}>"))? + start;
+    Some(xml[start..end].trim()).filter(|text| !text.is_empty())
+}
+
+/// Turns a .NET `Keys` value like `S, Shift, Control` into a binding like `ctrl+shift+s`.
+fn convert_key(keys: &str) -> Option<String> {
+    let mut modifiers = Vec::new();
+    let mut key = None;
+    for part in keys.split(',').map(str::trim) {
+        match part {
+            "Control" => modifiers.push("ctrl"),
+            "Shift" => modifiers.push("shift"),
+            "Alt" => modifiers.push("alt"),
+            part => key = Some(convert_key_name(part)?),
+        }
+    }
+    let mut binding = modifiers.join("+");
+    if !binding.is_empty() {
+        binding.push('+');
+    }
+    binding.push_str(&key?);
+    Some(binding)
+}
+
+fn convert_key_name(name: &str) -> Option<String> {
+    let key = match name {
+        "Space" => "space",
+        "Return" | "Enter" => "enter",
+        "Back" => "backspace",
+        "Tab" => "tab",
+        "Escape" => "esc",
+        "Delete" => "delete",
+        "Insert" => "insert",
+        "Home" => "home",
+        "End" => "end",
+        "PageUp" | "Prior" => "pageup",
+        "PageDown" | "Next" => "pagedown",
+        "Up" => "up",
+        "Down" => "down",
+        "Left" => "left",
+        "Right" => "right",
+        "Add" | "Oemplus" => "+",
+        "Subtract" | "OemMinus" => "-",
+        "Multiply" => "*",
+        "Divide" => "/",
+        "Decimal" | "OemPeriod" => ".",
+        "Oemcomma" => ",",
+        _ => {
+            let digit = name
+                .strip_prefix("NumPad")
+                .or_else(|| name.strip_prefix('D'))
+                .filter(|digit| digit.len() == 1 && digit.chars().all(|c| c.is_ascii_digit()));
+            if let Some(digit) = digit {
+                return Some(digit.to_string());
+            }
+            let is_function_key = name.len() > 1
+                && name.starts_with('F')
+                && name[1..].chars().all(|c| c.is_ascii_digit());
+            if is_function_key || (name.len() == 1 && name.chars().all(|c| c.is_ascii_alphabetic()))
+            {
+                return Some(name.to_lowercase());
+            }
+            return None;
+        }
+    };
+    Some(key.to_string())
+}
@@ -0,0 +1,484 @@
+use std::{fmt, str::FromStr};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Redraw,
+    Open,
+    Split,
+    Rewind,
+    Note,
+    History,
+    Save,
+    SaveCopy,
+    EnterTimes,
+    NextComparison,
+    PreviousComparison,
+    NextPreset,
+    ToggleTimingMethod,
+    ToggleTableTimingMethod,
+    SetTarget,
+    StartAt,
+    FreezeGameTime,
+    Stop,
+    ReloadConfig,
+    ReloadRun,
+    Info,
+    BigTimer,
+    MarkGold,
+    Stopwatch,
+    Library,
+    ExportGolds,
+    ImportGolds,
+    ImportComparison,
+    Snapshot,
+    ToggleDelta,
+    CursorUp,
+    CursorDown,
+    ClearHistory,
+    ResetGolds,
+}
+
+impl Action {
+    /// The action's name in words, e.g. `next comparison`.
+    pub fn name(self) -> String {
+        let mut name = String::new();
+        for c in format!("{self:?}").chars() {
+            if c.is_uppercase() && !name.is_empty() {
+                name.push(' ');
+            }
+            name.push(c.to_ascii_lowercase());
+        }
+        name
+    }
+}
+
+/// What the keys pressed so far add up to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Chord {
+    Action(Action),
+    /// The start of a longer binding, waiting for the next key.
+    Pending,
+    Unbound,
+}
+
+/// A single key press, parsed from strings like `space`, `ctrl+o` or `shift+tab`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBind {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBind {
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        // shift is already baked into the character for letters and symbols
+        let ignored = match key.code {
+            KeyCode::Char(_) | KeyCode::BackTab => KeyModifiers::SHIFT,
+            _ => KeyModifiers::NONE,
+        };
+        self.code == key.code && self.modifiers - ignored == key.modifiers - ignored
+    }
+}
+
+impl FromStr for KeyBind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let key = match parts.pop() {
+            // "ctrl++" binds the plus key
+            Some("") if s.ends_with('+') => {
+                parts.pop();
+                "+"
+            }
+            Some(key) if !key.is_empty() => key,
+            _ => return Err(format!("empty key binding {s:?}")),
+        };
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => return Err(format!("unknown modifier {other:?}")),
+            };
+        }
+        let code = match key.to_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            f if f.len() > 1 && f.starts_with('f') => match f[1..].parse() {
+                Ok(n) => KeyCode::F(n),
+                Err(_) => return Err(format!("unknown key {key:?}")),
+            },
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    // terminals send shifted letters as the uppercase character
+                    (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                        KeyCode::Char(c.to_ascii_uppercase())
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("unknown key {key:?}")),
+                }
+            }
+        };
+        Ok(Self { code, modifiers })
+    }
+}
+
+/// Writes the key the way it reads on a keyboard, e.g. `ctrl+o` as `CTRL + O`.
+impl fmt::Display for KeyBind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut modifiers = self.modifiers;
+        // an uppercase letter or backtab takes shift to type
+        match self.code {
+            KeyCode::Char(c) if c.is_uppercase() => modifiers |= KeyModifiers::SHIFT,
+            KeyCode::BackTab => modifiers |= KeyModifiers::SHIFT,
+            _ => (),
+        }
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "CTRL"),
+            (KeyModifiers::ALT, "ALT"),
+            (KeyModifiers::SHIFT, "SHIFT"),
+        ] {
+            if modifiers.contains(modifier) {
+                write!(f, "{name} + ")?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "SPACE"),
+            KeyCode::Char(c) => write!(f, "{}", c.to_uppercase()),
+            KeyCode::Enter => write!(f, "ENTER"),
+            KeyCode::Esc => write!(f, "ESC"),
+            KeyCode::Tab | KeyCode::BackTab => write!(f, "TAB"),
+            KeyCode::Backspace => write!(f, "BACKSPACE"),
+            KeyCode::Delete => write!(f, "DELETE"),
+            KeyCode::Insert => write!(f, "INSERT"),
+            KeyCode::Up => write!(f, "UP"),
+            KeyCode::Down => write!(f, "DOWN"),
+            KeyCode::Left => write!(f, "LEFT"),
+            KeyCode::Right => write!(f, "RIGHT"),
+            KeyCode::Home => write!(f, "HOME"),
+            KeyCode::End => write!(f, "END"),
+            KeyCode::PageUp => write!(f, "PAGEUP"),
+            KeyCode::PageDown => write!(f, "PAGEDOWN"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// Writes a binding the way it reads on a keyboard, e.g. `ctrl+o` as `CTRL + O`
+/// and `g g` as `G, G`.
+pub fn display(bind: &str) -> String {
+    bind.split_whitespace()
+        .map(|keys| match keys.parse::<KeyBind>() {
+            Ok(key) => key.to_string(),
+            Err(_) => keys.to_uppercase(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keybindings {
+    pub quit: String,
+    pub redraw: String,
+    pub open: String,
+    pub split: String,
+    pub rewind: String,
+    pub note: String,
+    pub history: String,
+    pub save: String,
+    pub save_copy: String,
+    pub enter_times: String,
+    pub next_comparison: String,
+    pub previous_comparison: String,
+    pub next_preset: String,
+    pub toggle_timing_method: String,
+    pub toggle_table_timing_method: String,
+    pub set_target: String,
+    pub start_at: String,
+    pub freeze_game_time: String,
+    pub stop: String,
+    pub reload_config: String,
+    pub reload_run: String,
+    pub info: String,
+    pub big_timer: String,
+    pub mark_gold: String,
+    pub stopwatch: String,
+    pub library: String,
+    pub export_golds: String,
+    pub import_golds: String,
+    pub import_comparison: String,
+    pub snapshot: String,
+    pub toggle_delta: String,
+    pub cursor_up: String,
+    pub cursor_down: String,
+    /// Unbound by default, since they rewrite the splits file.
+    pub clear_history: String,
+    pub reset_golds: String,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            quit: String::from("ctrl+c"),
+            redraw: String::from("ctrl+l"),
+            open: String::from("ctrl+o"),
+            split: String::from("space"),
+            rewind: String::from("backspace"),
+            note: String::from("n"),
+            history: String::from("h"),
+            save: String::from("ctrl+s"),
+            save_copy: String::from("alt+s"),
+            enter_times: String::from("ctrl+t"),
+            next_comparison: String::from("tab"),
+            previous_comparison: String::from("shift+tab"),
+            next_preset: String::from("p"),
+            toggle_timing_method: String::from("m"),
+            toggle_table_timing_method: String::from("t"),
+            set_target: String::from("ctrl+g"),
+            start_at: String::from("ctrl+a"),
+            freeze_game_time: String::from("f"),
+            stop: String::from("enter"),
+            reload_config: String::from("ctrl+r"),
+            reload_run: String::from("R"),
+            info: String::from("i"),
+            big_timer: String::from("b"),
+            mark_gold: String::from("g"),
+            stopwatch: String::from("w"),
+            library: String::from("l"),
+            export_golds: String::from("ctrl+e"),
+            import_golds: String::from("ctrl+b"),
+            import_comparison: String::from("ctrl+k"),
+            snapshot: String::from("ctrl+p"),
+            toggle_delta: String::from("d"),
+            cursor_up: String::from("up"),
+            cursor_down: String::from("down"),
+            clear_history: String::new(),
+            reset_golds: String::new(),
+        }
+    }
+}
+
+impl Keybindings {
+    fn bindings(&self) -> Vec<(Action, &str)> {
+        vec![
+            (Action::Quit, &self.quit),
+            (Action::Redraw, &self.redraw),
+            (Action::Open, &self.open),
+            (Action::Split, &self.split),
+            (Action::Rewind, &self.rewind),
+            (Action::Note, &self.note),
+            (Action::History, &self.history),
+            (Action::Save, &self.save),
+            (Action::SaveCopy, &self.save_copy),
+            (Action::EnterTimes, &self.enter_times),
+            (Action::NextComparison, &self.next_comparison),
+            (Action::PreviousComparison, &self.previous_comparison),
+            (Action::NextPreset, &self.next_preset),
+            (Action::ToggleTimingMethod, &self.toggle_timing_method),
+            (
+                Action::ToggleTableTimingMethod,
+                &self.toggle_table_timing_method,
+            ),
+            (Action::SetTarget, &self.set_target),
+            (Action::StartAt, &self.start_at),
+            (Action::FreezeGameTime, &self.freeze_game_time),
+            (Action::Stop, &self.stop),
+            (Action::ReloadConfig, &self.reload_config),
+            (Action::ReloadRun, &self.reload_run),
+            (Action::Info, &self.info),
+            (Action::BigTimer, &self.big_timer),
+            (Action::MarkGold, &self.mark_gold),
+            (Action::Stopwatch, &self.stopwatch),
+            (Action::Library, &self.library),
+            (Action::ExportGolds, &self.export_golds),
+            (Action::ImportGolds, &self.import_golds),
+            (Action::ImportComparison, &self.import_comparison),
+            (Action::Snapshot, &self.snapshot),
+            (Action::ToggleDelta, &self.toggle_delta),
+            (Action::CursorUp, &self.cursor_up),
+            (Action::CursorDown, &self.cursor_down),
+            (Action::ClearHistory, &self.clear_history),
+            (Action::ResetGolds, &self.reset_golds),
+        ]
+    }
+
+    /// Pairs of actions bound to the same keys, of which only the first ever fires,
+    /// with the keys they share.
+    pub fn conflicts(&self) -> Vec<(Action, Action, String)> {
+        let parsed: Vec<(Action, &str, Vec<KeyBind>)> = self
+            .bindings()
+            .into_iter()
+            .filter_map(|(action, bind)| {
+                let sequence = bind
+                    .split_whitespace()
+                    .map(str::parse::<KeyBind>)
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()?;
+                Some((action, bind, sequence)).filter(|(_, _, sequence)| !sequence.is_empty())
+            })
+            .collect();
+        let mut conflicts = Vec::new();
+        for (i, (first, bind, sequence)) in parsed.iter().enumerate() {
+            for (second, _, other) in &parsed[i + 1..] {
+                if sequence == other {
+                    conflicts.push((*first, *second, display(bind)));
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Adds a key press to the keys pressed so far, returning an action once they
+    /// make up a whole binding. `pending` is cleared whenever a binding is done with.
+    pub fn press(&self, pending: &mut Vec<KeyEvent>, key: KeyEvent) -> Option<Action> {
+        pending.push(key);
+        let mut chord = self.resolve(pending);
+        if chord == Chord::Unbound && pending.len() > 1 {
+            // the sequence went nowhere, but the last key might still mean something on its own
+            *pending = vec![key];
+            chord = self.resolve(pending);
+        }
+        match chord {
+            Chord::Action(action) => {
+                pending.clear();
+                Some(action)
+            }
+            Chord::Pending => None,
+            Chord::Unbound => {
+                pending.clear();
+                None
+            }
+        }
+    }
+
+    /// Looks up the keys pressed so far. Bindings can be sequences separated by
+    /// spaces, like `g g`, and a complete binding wins over a longer one it starts.
+    pub fn resolve(&self, keys: &[KeyEvent]) -> Chord {
+        let mut pending = false;
+        for (action, bind) in self.bindings() {
+            let Ok(sequence) = bind
+                .split_whitespace()
+                .map(str::parse::<KeyBind>)
+                .collect::<Result<Vec<_>, _>>()
+            else {
+                continue;
+            };
+            if sequence.len() < keys.len()
+                || !sequence
+                    .iter()
+                    .zip(keys)
+                    .all(|(bind, key)| bind.matches(key))
+            {
+                continue;
+            }
+            if sequence.len() == keys.len() {
+                return Chord::Action(action);
+            }
+            pending = true;
+        }
+        match pending {
+            true => Chord::Pending,
+            false => Chord::Unbound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bind(s: &str) -> KeyBind {
+        s.parse().unwrap()
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parses() {
+        assert_eq!(bind("space").code, KeyCode::Char(' '));
+        assert_eq!(
+            bind("ctrl+o"),
+            KeyBind {
+                code: KeyCode::Char('o'),
+                modifiers: KeyModifiers::CONTROL,
+            }
+        );
+        assert_eq!(bind("shift+tab").code, KeyCode::BackTab);
+        assert_eq!(bind("ctrl++").code, KeyCode::Char('+'));
+        assert_eq!(bind("f5").code, KeyCode::F(5));
+        assert!("".parse::<KeyBind>().is_err());
+        assert!("hyper+x".parse::<KeyBind>().is_err());
+    }
+
+    #[test]
+    fn shift_uppercases_letters() {
+        assert_eq!(bind("shift+s").code, KeyCode::Char('S'));
+        assert_eq!(bind("ctrl+shift+s").code, KeyCode::Char('S'));
+        assert_eq!(bind("R").code, KeyCode::Char('R'));
+    }
+
+    #[test]
+    fn displays() {
+        assert_eq!(display("ctrl+o"), "CTRL + O");
+        assert_eq!(display("ctrl++"), "CTRL + +");
+        assert_eq!(display("shift+s"), "SHIFT + S");
+        assert_eq!(display("R"), "SHIFT + R");
+        assert_eq!(display("shift+tab"), "SHIFT + TAB");
+        assert_eq!(display("space"), "SPACE");
+        assert_eq!(display("g g"), "G, G");
+    }
+
+    #[test]
+    fn finds_conflicts() {
+        assert!(Keybindings::default().conflicts().is_empty());
+        let keybindings = Keybindings {
+            split: String::from("enter"),
+            ..Default::default()
+        };
+        assert_eq!(
+            keybindings.conflicts(),
+            vec![(Action::Split, Action::Stop, String::from("ENTER"))]
+        );
+        assert_eq!(Action::NextComparison.name(), "next comparison");
+    }
+
+    #[test]
+    fn matches() {
+        let shifted = key(KeyCode::Char('S'), KeyModifiers::SHIFT);
+        assert!(bind("shift+s").matches(&shifted));
+        assert!(!bind("s").matches(&shifted));
+        assert!(bind("S").matches(&shifted));
+        assert!(bind("ctrl+shift+s").matches(&key(
+            KeyCode::Char('S'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT
+        )));
+        assert!(bind("ctrl+o").matches(&key(KeyCode::Char('o'), KeyModifiers::CONTROL)));
+        assert!(!bind("ctrl+o").matches(&key(KeyCode::Char('o'), KeyModifiers::NONE)));
+        assert!(bind("shift+tab").matches(&key(KeyCode::BackTab, KeyModifiers::SHIFT)));
+    }
+}
@@ -0,0 +1,128 @@
+//! Finding splits files to pick from, in `splits_dir` and the configured library paths.
+
+use std::path::{Path, PathBuf};
+
+use tui::widgets::ListState;
+
+use crate::config::Config;
+
+/// The splits file picker.
+#[derive(Debug, Default)]
+pub struct Library {
+    pub files: Vec<PathBuf>,
+    pub state: ListState,
+}
+
+impl Library {
+    pub fn new(config: &Config) -> Self {
+        let files = scan(config);
+        let mut state = ListState::default();
+        state.select((!files.is_empty()).then_some(0));
+        Self { files, state }
+    }
+
+    pub fn selected(&self) -> Option<&Path> {
+        self.state
+            .selected()
+            .and_then(|i| self.files.get(i))
+            .map(PathBuf::as_path)
+    }
+
+    pub fn select_next(&mut self) {
+        if let Some(i) = self.state.selected() {
+            self.state.select(Some((i + 1).min(self.files.len() - 1)));
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if let Some(i) = self.state.selected() {
+            self.state.select(Some(i.saturating_sub(1)));
+        }
+    }
+}
+
+/// Every `.lss` under `splits_dir` and the library paths, which are either
+/// directories or globs like `~/speedruns/**/*.lss`. Directories are searched
+/// `library_depth` levels deep.
+pub fn scan(config: &Config) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in config.splits_dir.iter().chain(&config.library_paths) {
+        let entry = expand_home(entry);
+        let (base, pattern, depth) = match entry.find(['*', '?']) {
+            Some(wildcard) => {
+                // everything up to the last separator before the first wildcard is a plain directory
+                let base = entry[..wildcard].rfind('/').map_or("", |i| &entry[..i]);
+                let pattern = entry[base.len()..].trim_start_matches('/');
+                let depth = match pattern.contains("**") {
+                    true => config.library_depth,
+                    false => pattern.matches('/').count() + 1,
+                };
+                (base, pattern, depth)
+            }
+            None => (entry.as_str(), "**/*.lss", config.library_depth),
+        };
+        let base = if base.is_empty() { "." } else { base };
+        walk(Path::new(base), depth, &mut |path| {
+            let relative = path.strip_prefix(base).unwrap_or(path);
+            if glob_match(pattern, &relative.to_string_lossy()) && !files.iter().any(|f| f == path)
+            {
+                files.push(path.to_path_buf());
+            }
+        });
+    }
+    files.sort();
+    files
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), directories::BaseDirs::new()) {
+        (Some(rest), Some(dirs)) => dirs.home_dir().join(rest).to_string_lossy().into_owned(),
+        _ => path.to_string(),
+    }
+}
+
+/// Calls `visit` with every file below `dir`, going at most `depth` directories down.
+/// Unreadable directories are skipped.
+fn walk(dir: &Path, depth: usize, visit: &mut dyn FnMut(&Path)) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() && depth > 1 => walk(&path, depth - 1, visit),
+            Ok(kind) if kind.is_file() => visit(&path),
+            _ => {}
+        }
+    }
+}
+
+/// Matches a path against a glob where `*` and `?` stay within one directory
+/// and `**` spans any number of them.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    if let Some(rest) = pattern.strip_prefix("**") {
+        let rest = rest.strip_prefix('/').unwrap_or(rest);
+        return (0..=text.len())
+            .filter(|&i| text.is_char_boundary(i))
+            .any(|i| glob_match(rest, &text[i..]));
+    }
+    let mut chars = pattern.chars();
+    match chars.next() {
+        None => text.is_empty(),
+        Some('*') => {
+            let rest = chars.as_str();
+            let segment_end = text.find('/').unwrap_or(text.len());
+            (0..=segment_end)
+                .filter(|&i| text.is_char_boundary(i))
+                .any(|i| glob_match(rest, &text[i..]))
+        }
+        Some('?') => {
+            let mut text_chars = text.chars();
+            matches!(text_chars.next(), Some(c) if c != '/')
+                && glob_match(chars.as_str(), text_chars.as_str())
+        }
+        Some(c) => text
+            .strip_prefix(c)
+            .is_some_and(|text| glob_match(chars.as_str(), text)),
+    }
+}
@@ -0,0 +1,154 @@
+use std::path::Path;
+
+use livesplit::{
+    run::parser::{self, composite::ParsedRun, TimerKind},
+    Run, TimeSpan, TimingMethod,
+};
+
+/// The newest LiveSplit file format the parser knows, anything added after it is dropped.
+const NEWEST_LSS_VERSION: (u32, u32, u32) = (1, 8, 0);
+
+/// Parses a splits file, explaining what went wrong if no parser accepts it.
+pub fn parse_run(bytes: &[u8], path: Option<&Path>) -> Result<ParsedRun<'static>, String> {
+    parser::parse_and_fix(bytes, path)
+        .map(ParsedRun::into_owned)
+        .map_err(|_| describe_parse_failure(bytes))
+}
+
+/// The composite parser only reports that nothing matched, so guess what kind of
+/// file this is from its contents and, for LiveSplit files, ask that parser why.
+fn describe_parse_failure(bytes: &[u8]) -> String {
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return String::from("not a text file, is this really a splits file?");
+    };
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.is_empty() {
+        String::from("the file is empty")
+    } else if text.starts_with('<') {
+        if !text.contains("<Run") {
+            return String::from("XML file, but not a splits format shplit knows");
+        }
+        match parser::livesplit::parse(text) {
+            Err(err) => format!("corrupt LiveSplit file ({err})"),
+            Ok(_) => String::from("corrupt LiveSplit file"),
+        }
+    } else if text.starts_with('{') || text.starts_with('[') {
+        String::from("JSON file, but not a splits format shplit knows")
+    } else {
+        String::from("unrecognized splits format")
+    }
+}
+
+/// Looks for times that can't be right given the rest of the file, like a split
+/// earlier than the one before it, a segment faster than its gold, or an attempt
+/// whose segment times don't add up to its final time. These usually come from
+/// hand editing or a broken export.
+pub fn consistency_warnings(run: &Run) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for method in [TimingMethod::RealTime, TimingMethod::GameTime] {
+        let suffix = match method {
+            TimingMethod::RealTime => "",
+            TimingMethod::GameTime => " (game time)",
+        };
+        let mut previous = TimeSpan::zero();
+        // the golds of the segments since the last split, which a skipped split lumps together
+        let mut best = Some(TimeSpan::zero());
+        for segment in run.segments() {
+            best = best
+                .zip(segment.best_segment_time()[method])
+                .map(|(a, b)| a + b);
+            let Some(split) = segment.personal_best_split_time()[method] else {
+                continue;
+            };
+            let segment_time = split - previous;
+            if segment_time < TimeSpan::zero() {
+                warnings.push(format!(
+                    "{}: PB split is before the one before it{suffix}",
+                    segment.name()
+                ));
+            } else if best.is_some_and(|best| segment_time < best) {
+                warnings.push(format!(
+                    "{}: PB segment is faster than the gold{suffix}",
+                    segment.name()
+                ));
+            }
+            previous = split;
+            best = Some(TimeSpan::zero());
+        }
+        let mismatched: Vec<String> = run
+            .attempt_history()
+            .iter()
+            .filter(|attempt| {
+                let Some(total) = attempt.time()[method] else {
+                    return false;
+                };
+                segment_sum(run, attempt.index(), method)
+                    .is_some_and(|sum| (sum - total).total_seconds().abs() > 0.001)
+            })
+            .map(|attempt| format!("#{}", attempt.index()))
+            .collect();
+        if !mismatched.is_empty() {
+            warnings.push(format!(
+                "attempts {}: the segment times don't add up to the final time{suffix}",
+                mismatched.join(", ")
+            ));
+        }
+    }
+    warnings
+}
+
+/// The attempt's segment times added up, or `None` if a segment didn't keep its
+/// time from that attempt. A skipped split's time is counted in the next segment.
+fn segment_sum(run: &Run, attempt: i32, method: TimingMethod) -> Option<TimeSpan> {
+    run.segments()
+        .iter()
+        .try_fold(TimeSpan::zero(), |sum, segment| {
+            let time = segment.segment_history().get(attempt)?;
+            Some(time[method].map_or(sum, |time| sum + time))
+        })
+}
+
+/// Points out files the parser only partly understands, so data missing from the
+/// run doesn't come as a surprise: LiveSplit files from before the format had a
+/// version or newer than shplit knows, and files from other timers.
+pub fn compatibility_warnings(bytes: &[u8], kind: &TimerKind) -> Vec<String> {
+    if *kind != TimerKind::LiveSplit {
+        return vec![format!(
+            "imported from {kind}, which doesn't keep everything a LiveSplit file does, \
+             so things like attempt history may be missing"
+        )];
+    }
+    let text = String::from_utf8_lossy(bytes);
+    let version = text
+        .split_once("<Run")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .and_then(|(attributes, _)| attributes.split_once("version=\""))
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .map(|(version, _)| version);
+    let Some(version) = version else {
+        return vec![String::from(
+            "the file is from a LiveSplit older than 1.4, \
+             attempt history and game times may be missing",
+        )];
+    };
+    let mut parts = version.split('.').map(|part| part.parse::<u32>());
+    let parsed = match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor)), patch) => {
+            (major, minor, patch.and_then(Result::ok).unwrap_or(0))
+        }
+        _ => return vec![format!("unknown LiveSplit file version {version:?}")],
+    };
+    if parsed < (1, 4, 0) {
+        vec![format!(
+            "the file is from LiveSplit {version}, attempt history and game times may be missing"
+        )]
+    } else if parsed > NEWEST_LSS_VERSION {
+        let (major, minor, patch) = NEWEST_LSS_VERSION;
+        vec![format!(
+            "the file is in LiveSplit format {version}, \
+             anything added after {major}.{minor}.{patch} wasn't loaded"
+        )]
+    } else {
+        Vec::new()
+    }
+}
@@ -1,27 +1,209 @@
+#[cfg(feature = "audio-cue")]
+mod audio;
+mod bigtext;
+mod client;
 mod config;
+mod daemon;
+mod daily;
+mod golds;
+mod import;
+mod keybinds;
+mod library;
+mod load;
+mod maintenance;
+mod manual;
+mod notes;
+mod race;
+mod server;
+mod sidecar;
+#[cfg(feature = "snapshot")]
+mod snapshot;
+#[cfg(feature = "sqlite-export")]
+mod sqlite;
+mod theme;
+mod timefmt;
+mod ui;
 use config::*;
+use daily::DailyStats;
+use golds::SharedGolds;
+use keybinds::{Action, Chord, KeyBind};
+use library::Library;
+use maintenance::Maintenance;
+use notes::AttemptNotes;
+use sidecar::Sidecar;
+use timefmt::*;
 
 use crossterm::{
     event::{
         self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
-        Event, KeyCode, KeyEventKind, KeyModifiers,
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEventKind,
     },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use livesplit::run::parser::TimerKind;
 use std::{
     error::Error,
-    io,
-    path::PathBuf,
-    time::{Duration, Instant},
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime},
 };
 use tui::{prelude::*, widgets::*};
 
+#[derive(Debug)]
+enum PromptKind {
+    /// Editing the note of the attempt with this history index.
+    Note(i32),
+    /// Typing in the split times of a run timed elsewhere, one segment at a time.
+    SplitTimes(Vec<Option<livesplit::TimeSpan>>),
+    /// A one-off total time to chase, shown in the footer.
+    Target,
+    /// Saving a run loaded from another timer's format as `.lss`, named after that timer.
+    Convert(String),
+    /// Typing "yes" before converting over a file that's already at this path.
+    Overwrite(String),
+    /// Confirming the category of a run just loaded, or turning it down to pick another file.
+    Category(String),
+    /// The path of a golds file to add as a comparison.
+    ImportGolds,
+    /// Typing "yes" before a destructive clean-up of the splits file.
+    Confirm(Maintenance),
+    /// The time to start the clock from, e.g. to pick an interrupted run back up.
+    StartAt,
+    /// The path of another splits file to race against.
+    RaceFile,
+    /// Which of that file's comparisons to import, named after the file.
+    RaceComparison(Box<livesplit::Run>, String),
+}
+
+/// A single line of text input shown in the footer.
+#[derive(Debug)]
+struct Prompt {
+    kind: PromptKind,
+    text: String,
+}
+
+/// How long the "saved" indicator stays in the footer after writing the splits file.
+const SAVED_DURATION: Duration = Duration::from_secs(2);
+/// A splits file written this recently when loading is probably still being saved by something.
+const RECENT_WRITE: Duration = Duration::from_secs(2);
+/// Shown when settings can't be written anywhere.
+const NOT_PERSISTENT: &str = "no writable config directory, settings won't be saved";
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// Writes out a splits file unless another program changed it since `mtime`, which
+/// then moves along to this write. Only refuses once, writing again overwrites.
+fn write_splits(
+    path: &Path,
+    lss: &str,
+    mtime: &mut Option<SystemTime>,
+) -> Result<(), Box<dyn Error>> {
+    let on_disk = modified_time(path);
+    if mtime.is_some() && on_disk != *mtime {
+        *mtime = on_disk;
+        return Err(String::from(
+            "the file was changed by another program since it was loaded, save again to overwrite it",
+        )
+        .into());
+    }
+    std::fs::write(path, lss)?;
+    *mtime = modified_time(path);
+    Ok(())
+}
+
+/// Sets `terminate` when the system asks shplit to close, so it can restore the
+/// terminal first. Without this, being closed by a session manager or with the
+/// window leaves the terminal in raw mode. A second signal exits right away, in
+/// case the first one is never noticed.
+fn watch_exit_signals(terminate: &Arc<AtomicBool>) {
+    #[cfg(unix)]
+    for signal in [
+        signal_hook::consts::SIGTERM,
+        signal_hook::consts::SIGHUP,
+        signal_hook::consts::SIGINT,
+    ] {
+        // runs before the flag is set below, so only once it's already set
+        signal_hook::flag::register_conditional_shutdown(signal, 1, Arc::clone(terminate)).ok();
+        signal_hook::flag::register(signal, Arc::clone(terminate)).ok();
+    }
+    #[cfg(not(unix))]
+    let _ = terminate;
+}
+
 #[derive(Debug)]
 struct App {
     timer: Option<livesplit::Timer>,
     table_state: TableState,
     config: Config,
+    file_states: FileStates,
+    notes: AttemptNotes,
+    daily: DailyStats,
+    prompt: Option<Prompt>,
+    /// The splits file picker, while it's open.
+    library: Option<Library>,
+    show_history: bool,
+    show_info: bool,
+    /// Show nothing but a huge clock until the next key press.
+    big_timer: bool,
+    /// Problems found in the loaded splits file, shown in the info panel.
+    warnings: Vec<String>,
+    status: Option<(String, Instant)>,
+    last_saved: Option<Instant>,
+    /// Set from a signal handler when the system asks shplit to quit.
+    terminate: Arc<AtomicBool>,
+    /// When the splits file was last written as far as shplit knows, to notice
+    /// another program writing to it in the meantime.
+    file_mtime: Option<SystemTime>,
+    /// The segment that was split last and when, for showing its result for a moment.
+    last_split: Option<(usize, Instant)>,
+    /// Segments marked as golds by hand with the timing method they were timed in and
+    /// their segment time, saved with the run.
+    marked_golds: Vec<(usize, livesplit::TimingMethod, livesplit::TimeSpan)>,
+    /// Timing without a splits file, with splitting taking laps instead.
+    stopwatch: bool,
+    /// Total time at each lap taken in stopwatch mode.
+    laps: Vec<livesplit::TimeSpan>,
+    target: Option<livesplit::TimeSpan>,
+    /// The first table row shown after scrolling with the mouse, and the split the timer was on
+    /// then. Following the timer picks back up once it moves on.
+    table_scroll: Option<(usize, Option<usize>)>,
+    /// A segment picked with the arrow keys to look at, and the split the timer was on then.
+    /// Like scrolling, the table goes back to following the timer once it moves on.
+    table_cursor: Option<(usize, Option<usize>)>,
+    /// The splits file as reloaded during an attempt, with its modification time, waiting
+    /// for the attempt to finish before it takes over.
+    pending_reload: Option<(livesplit::Run, Option<SystemTime>)>,
+    /// The splits file's own start offset while the timer runs from a time typed in,
+    /// which is what gets saved instead.
+    file_offset: Option<livesplit::TimeSpan>,
+    /// The preset applied last, to move on from with the next press.
+    preset: Option<usize>,
+    /// The table's timing method when it differs from the clock's.
+    table_method: Option<livesplit::TimingMethod>,
+    /// Keys pressed so far towards a multi-key binding.
+    pending_keys: Vec<KeyEvent>,
+    /// When the current attempt started and each of its splits happened, in local time.
+    split_clock: Vec<time::OffsetDateTime>,
+    /// Looked up once at launch, since that's only reliable before other threads exist.
+    local_offset: time::UtcOffset,
+    /// Drives animations like the marquee.
+    launched: Instant,
+    /// False when there's nowhere to write the config, so settings only live in memory.
+    config_writable: bool,
+    #[cfg(feature = "audio-cue")]
+    audio: Option<std::sync::mpsc::Receiver<audio::AudioEvent>>,
+    /// Save the next frame as an image once it's drawn.
+    #[cfg(feature = "snapshot")]
+    snapshot_requested: bool,
 }
 
 impl Default for App {
@@ -30,55 +212,1479 @@ impl Default for App {
             timer: Default::default(),
             table_state: Default::default(),
             config: Config::load().unwrap_or_default(),
+            file_states: FileStates::load().unwrap_or_default(),
+            notes: Default::default(),
+            daily: Default::default(),
+            prompt: None,
+            library: None,
+            show_history: false,
+            show_info: false,
+            big_timer: false,
+            warnings: Vec::new(),
+            status: None,
+            last_saved: None,
+            terminate: Arc::new(AtomicBool::new(false)),
+            file_mtime: None,
+            last_split: None,
+            marked_golds: Vec::new(),
+            stopwatch: false,
+            laps: Vec::new(),
+            target: None,
+            table_scroll: None,
+            table_cursor: None,
+            pending_reload: None,
+            file_offset: None,
+            preset: None,
+            table_method: None,
+            pending_keys: Vec::new(),
+            split_clock: Vec::new(),
+            local_offset: time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC),
+            launched: Instant::now(),
+            config_writable: config_writable(),
+            #[cfg(feature = "audio-cue")]
+            audio: None,
+            #[cfg(feature = "snapshot")]
+            snapshot_requested: false,
+        };
+        if !app.config_writable {
+            app.set_status(NOT_PERSISTENT);
+        }
+        app.warn_conflicts();
+        app.table_method = app.config.table_timing_method;
+        #[cfg(feature = "audio-cue")]
+        {
+            app.audio = app.config.audio_cue.clone().map(audio::listen);
+        }
+        let split_file = match app.config.splits_dir.clone() {
+            Some(dir) if app.config.open_latest => match latest_splits_file(Path::new(&dir)) {
+                Some(path) => Some(path.to_string_lossy().into_owned()),
+                None => {
+                    app.set_status(format!("no splits files in {dir}"));
+                    None
+                }
+            },
+            _ => app.config.split_file.clone(),
         };
-        if let Some(split_file) = app.config.split_file.clone() {
-            app.load_run(split_file).ok(); // dont care if it fails lol
+        if let Some(split_file) = split_file {
+            if let Err(err) = app.load_run(&split_file) {
+                app.set_status(format!("couldn't load {split_file}: {err}"));
+            }
         }
         app
     }
 }
 
+/// The `.lss` file in `dir` that was changed last, if it has any and can be read.
+fn latest_splits_file(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("lss"))
+        })
+        .filter_map(|path| Some((modified_time(&path)?, path)))
+        .max()
+        .map(|(_, path)| path)
+}
+
 impl App {
     fn load_run<A: Into<PathBuf>>(&mut self, path: A) -> Result<(), Box<dyn Error>> {
         let path: PathBuf = path.into();
         if path.try_exists()? {
-            self.config.split_file = Some(path.clone().to_str().unwrap().to_string());
+            let file_mtime = modified_time(&path);
             let bytes = std::fs::read(&path)?;
-            let run = livesplit::run::parser::parse_and_fix(&bytes, Some(&path))?.run;
-            self.timer = Some(livesplit::Timer::new(run)?);
+            let parsed = load::parse_run(&bytes, Some(&path))?;
+            let warnings = load::consistency_warnings(&parsed.run);
+            let compatibility = load::compatibility_warnings(&bytes, &parsed.kind);
+            let mut timer =
+                livesplit::Timer::new(parsed.run).map_err(|_| "the splits file has no segments")?;
+            let split_file = path.clone().to_str().unwrap().to_string();
+            let file_state = self.file_states.get(&split_file);
+            if let Some(comparison) = file_state.comparison {
+                // falls back to the personal best if the comparison is gone
+                timer.set_current_comparison(comparison).ok();
+            }
+            if let Some(method) = file_state.timing_method {
+                timer.set_current_timing_method(method);
+            }
+            self.timer = Some(timer);
+            self.stopwatch = false;
+            self.marked_golds.clear();
+            self.split_clock.clear();
+            self.pending_reload = None;
+            self.file_offset = None;
+            self.table_cursor = None;
+            self.table_scroll = None;
+            self.config.split_file = Some(split_file);
+            self.notes = AttemptNotes::load(&path).unwrap_or_default();
+            self.daily = DailyStats::load(&path).unwrap_or_default();
+            self.announce_category();
+            self.set_warnings(warnings, compatibility);
+            self.file_mtime = file_mtime;
+            if file_mtime
+                .and_then(|mtime| mtime.elapsed().ok())
+                .is_some_and(|age| age < RECENT_WRITE)
+            {
+                self.set_status(
+                    "the file was written just now, it might still be in use elsewhere",
+                );
+            }
+            if parsed.kind != TimerKind::LiveSplit {
+                self.prompt = Some(Prompt {
+                    kind: PromptKind::Convert(parsed.kind.to_string()),
+                    text: path.with_extension("lss").to_string_lossy().into_owned(),
+                });
+            }
             Ok(())
         } else {
             Err(String::from("file not found").into())
         }
     }
+
+    /// Asks to confirm the category along with its variables when it has any, e.g.
+    /// "Any% (Hard)", so a wrong file stands out before the run starts.
+    fn announce_category(&mut self) {
+        let Some(ref timer) = self.timer else {
+            return;
+        };
+        let run = timer.run();
+        if run.metadata().speedrun_com_variables().next().is_none() {
+            return;
+        }
+        let category = run.extended_category_name(false, false, true).to_string();
+        self.prompt = Some(Prompt {
+            kind: PromptKind::Category(format!("{} {category}", run.game_name())),
+            text: String::new(),
+        });
+    }
+
+    /// Keeps the warnings for the info panel, pointing there from the status line.
+    /// Compatibility problems come first since they explain the others.
+    fn set_warnings(&mut self, warnings: Vec<String>, compatibility: Vec<String>) {
+        let info = keybinds::display(&self.config.keybindings.info);
+        if !compatibility.is_empty() {
+            self.set_status(format!(
+                "some of the splits file may not have loaded, press {info} for details"
+            ));
+        } else if !warnings.is_empty() {
+            self.set_status(format!(
+                "the splits file looks inconsistent, press {info} for details"
+            ));
+        }
+        self.warnings = compatibility.into_iter().chain(warnings).collect();
+    }
+
+    /// Loads a run piped in, which has no file to save back to.
+    fn load_stdin(&mut self, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+        let parsed = load::parse_run(bytes, None)?;
+        let warnings = load::consistency_warnings(&parsed.run);
+        let compatibility = load::compatibility_warnings(bytes, &parsed.kind);
+        let timer =
+            livesplit::Timer::new(parsed.run).map_err(|_| "the splits file has no segments")?;
+        self.timer = Some(timer);
+        self.stopwatch = false;
+        self.marked_golds.clear();
+        self.split_clock.clear();
+        self.pending_reload = None;
+        self.file_offset = None;
+        self.table_cursor = None;
+        self.table_scroll = None;
+        self.config.split_file = None;
+        self.notes = AttemptNotes::default();
+        self.daily = DailyStats::default();
+        self.announce_category();
+        self.set_warnings(warnings, compatibility);
+        self.file_mtime = None;
+        Ok(())
+    }
+
+    /// Loads a run picked by the user, remembering it for next time or reporting why it failed.
+    fn open_run(&mut self, path: &Path) {
+        match self.load_run(path) {
+            Ok(()) => {
+                self.config.save().ok();
+            }
+            Err(err) => {
+                let name = path.file_name().unwrap_or(path.as_os_str());
+                self.set_status(format!("couldn't load {}: {err}", name.to_string_lossy()));
+            }
+        }
+    }
+
+    /// Lines taken up when rendering inline, enough to fit the whole run by default.
+    fn inline_height(&self) -> u16 {
+        self.config
+            .inline_height
+            .unwrap_or_else(|| match self.timer {
+                // rows and section headers, borders, header, footer, delta strip and big clock
+                Some(ref timer) => {
+                    let clock = match self.config.theme.clock.big {
+                        true => bigtext::size("0", 1).1 as u16,
+                        false => 0,
+                    };
+                    let header = match self.config.hide_header {
+                        true => 0,
+                        false => 2,
+                    };
+                    ui::table_rows(&self.config, timer.run().len()) as u16
+                        + 3
+                        + header
+                        + !self.config.reference_deltas.is_empty() as u16
+                        + clock
+                }
+                None => 5,
+            })
+    }
+
+    /// Keeps the table's selection on the split the timer is on.
+    fn sync_table_state(&mut self) {
+        if let Some(ref timer) = self.timer {
+            if self
+                .table_scroll
+                .is_some_and(|(_, split)| split != timer.current_split_index())
+            {
+                self.table_scroll = None;
+            }
+            if self
+                .table_cursor
+                .is_some_and(|(_, split)| split != timer.current_split_index())
+            {
+                self.table_cursor = None;
+            }
+            let len = timer.run().len();
+            let segment = match self.table_cursor {
+                Some((segment, _)) => Some(segment.min(len - 1)),
+                None => timer.current_split_index(),
+            };
+            self.table_state
+                .select(segment.map(|segment| ui::table_row(&self.config, len, segment)));
+        }
+    }
+
+    /// Moves the table's highlight to the segment above or below, wrapping around
+    /// at the ends if configured. The table scrolls along with it.
+    fn move_cursor(&mut self, down: bool) {
+        let Some(ref timer) = self.timer else {
+            return;
+        };
+        let last = timer.run().len() - 1;
+        let current = self
+            .table_cursor
+            .map(|(segment, _)| segment)
+            .or(timer.current_split_index())
+            .unwrap_or(0)
+            .min(last);
+        let wrap = self.config.wrap_navigation;
+        // the keys go by what's on screen, which is upside down when reversed
+        let down = down != self.config.reverse_order;
+        let segment = match (down, current) {
+            (true, current) if current == last => match wrap {
+                true => 0,
+                false => last,
+            },
+            (true, current) => current + 1,
+            (false, 0) => match wrap {
+                true => last,
+                false => 0,
+            },
+            (false, current) => current - 1,
+        };
+        self.table_scroll = None;
+        self.table_cursor = Some((segment, timer.current_split_index()));
+    }
+
+    /// Scrolls the table by the configured step, away from wherever the timer is.
+    fn scroll_table(&mut self, down: bool) {
+        let Some(ref timer) = self.timer else {
+            return;
+        };
+        let len = timer.run().len();
+        let rows = ui::table_rows(&self.config, len);
+        let offset = self
+            .table_scroll
+            .map_or(self.table_state.offset(), |(offset, _)| offset);
+        let offset = match down {
+            true => (offset + self.config.scroll_step).min(rows - 1),
+            false => offset.saturating_sub(self.config.scroll_step),
+        };
+        self.table_scroll = Some((offset, timer.current_split_index()));
+    }
+
+    /// Splits on audio cues heard since the last frame, only while a run is going.
+    #[cfg(feature = "audio-cue")]
+    fn poll_audio(&mut self) {
+        let Some(ref receiver) = self.audio else {
+            return;
+        };
+        let events: Vec<_> = receiver.try_iter().collect();
+        for event in events {
+            match event {
+                audio::AudioEvent::Cue => {
+                    if self
+                        .timer
+                        .as_ref()
+                        .is_some_and(|timer| timer.current_phase().is_running())
+                    {
+                        self.split();
+                    }
+                }
+                audio::AudioEvent::Failed(err) => {
+                    self.audio = None;
+                    self.set_status(err);
+                }
+            }
+        }
+    }
+
+    /// Reads the splits file again after it was edited elsewhere. An attempt in progress
+    /// keeps going if the segments are still the same, and the reloaded run takes over
+    /// once it's left behind, with the attempt added to it. A finished attempt is added
+    /// to the reloaded run straight away.
+    fn reload_run(&mut self) {
+        let Some(path) = self.config.split_file.clone().map(PathBuf::from) else {
+            self.set_status("there's no splits file to reload");
+            return;
+        };
+        let Some(phase) = self.timer.as_ref().map(|timer| timer.current_phase()) else {
+            return;
+        };
+        if phase == livesplit::TimerPhase::NotRunning {
+            match self.load_run(&path) {
+                Ok(()) => self.set_status("splits reloaded"),
+                Err(err) => self.set_status(format!("couldn't reload: {err}")),
+            }
+            return;
+        }
+        let mtime = modified_time(&path);
+        let parsed = match std::fs::read(&path) {
+            Ok(bytes) => load::parse_run(&bytes, Some(&path)),
+            Err(err) => Err(err.to_string()),
+        };
+        let run = match parsed {
+            Ok(parsed) => parsed.run,
+            Err(err) => {
+                self.set_status(format!("couldn't reload: {err}"));
+                return;
+            }
+        };
+        let current = self.timer.as_ref().unwrap().run();
+        let unchanged = run.len() == current.len()
+            && run
+                .segments()
+                .iter()
+                .zip(current.segments())
+                .all(|(new, old)| new.name() == old.name());
+        if unchanged {
+            self.pending_reload = Some((run, mtime));
+            match phase {
+                livesplit::TimerPhase::Ended => {
+                    self.apply_pending_reload();
+                    self.set_status("splits reloaded, with the finished attempt added");
+                }
+                _ => self.set_status("the reloaded splits take over once this attempt is over"),
+            }
+            return;
+        }
+        match self.load_run(&path) {
+            Ok(()) => self.set_status("the segments changed, so the attempt was dropped"),
+            Err(err) => self.set_status(format!("couldn't reload: {err}")),
+        }
+    }
+
+    /// The run reloaded during the attempt, with the attempt recorded in it the way
+    /// the timer would have.
+    fn merged_reload(&self) -> Option<livesplit::Run> {
+        let (timer, (run, _)) = (self.timer.as_ref()?, self.pending_reload.as_ref()?);
+        let mut run = run.clone();
+        manual::merge_attempt(&mut run, timer);
+        Some(run)
+    }
+
+    /// Swaps in a run reloaded during the attempt, now that the attempt is over.
+    fn apply_pending_reload(&mut self) {
+        let (Some(mut run), Some(timer)) = (self.merged_reload(), &self.timer) else {
+            return;
+        };
+        // the segments are the same, so the marks still point at the right ones
+        for &(i, method, time) in &self.marked_golds {
+            run.segment_mut(i).best_segment_time_mut()[method] = Some(time);
+        }
+        let method = timer.current_timing_method();
+        let comparison = timer.current_comparison().to_string();
+        let Ok(mut timer) = livesplit::Timer::new(run) else {
+            return;
+        };
+        timer.set_current_comparison(comparison).ok();
+        timer.set_current_timing_method(method);
+        self.timer = Some(timer);
+        self.file_mtime = self.pending_reload.take().and_then(|(_, mtime)| mtime);
+        self.file_offset = None;
+        self.table_cursor = None;
+        self.table_scroll = None;
+        self.marked_golds.clear();
+    }
+
+    /// Picks up edits to the config file, keeping the splits file that's open.
+    fn reload_config(&mut self) {
+        match Config::load() {
+            Ok(config) => {
+                let split_file = self.config.split_file.take();
+                self.config = Config {
+                    split_file,
+                    ..config
+                };
+                self.table_method = self.config.table_timing_method;
+                self.pending_keys.clear();
+                self.set_status("config reloaded");
+                self.warn_conflicts();
+            }
+            Err(err) => self.set_status(format!("couldn't reload config: {err}")),
+        }
+    }
+
+    /// Points out two actions bound to the same keys, since only one of them works.
+    fn warn_conflicts(&mut self) {
+        let conflicts = self.config.keybindings().conflicts();
+        if let Some((first, second, keys)) = conflicts.first() {
+            self.set_status(format!(
+                "{} and {} are both bound to {keys}, only {} works",
+                first.name(),
+                second.name(),
+                first.name()
+            ));
+        }
+    }
+
+    /// Feeds a key press into the bindings, returning an action once a whole binding is pressed.
+    fn action_for(&mut self, key: KeyEvent) -> Option<Action> {
+        self.config.keybindings().press(&mut self.pending_keys, key)
+    }
+
+    fn set_status<S: Into<String>>(&mut self, text: S) {
+        self.status = Some((text.into(), Instant::now()));
+    }
+
+    fn current_status(&self) -> Option<&str> {
+        self.status
+            .as_ref()
+            .filter(|(_, since)| since.elapsed() < ui::STATUS_DURATION)
+            .map(|(text, _)| text.as_str())
+    }
+
+    /// Writes the run, including any attempt in progress, back to the splits file.
+    fn save_run(&mut self) -> Result<(), Box<dyn Error>> {
+        // the attempt goes into the splits reloaded during it rather than over them
+        let merged = self.merged_reload();
+        if merged.is_some() {
+            self.file_mtime = self.pending_reload.as_ref().and_then(|(_, mtime)| *mtime);
+        }
+        let (Some(timer), Some(split_file)) = (&mut self.timer, &self.config.split_file) else {
+            return Err(String::from("no splits file loaded").into());
+        };
+        let mut lss = String::new();
+        let reloaded = merged.is_some();
+        if !reloaded && self.marked_golds.is_empty() && self.file_offset.is_none() {
+            livesplit::run::saver::livesplit::save_timer(timer, &mut lss)?;
+        } else {
+            // the timer doesn't hand out its run mutably, so apply them to the saved copy
+            let mut run = match (merged, timer.current_phase()) {
+                (Some(run), _) => run,
+                (None, livesplit::TimerPhase::NotRunning) => timer.run().clone(),
+                (None, _) => timer.clone().into_run(true),
+            };
+            for &(i, method, time) in &self.marked_golds {
+                run.segment_mut(i).best_segment_time_mut()[method] = Some(time);
+            }
+            // the reloaded run brings its own offset
+            if let Some(offset) = self.file_offset.filter(|_| !reloaded) {
+                run.set_offset(offset);
+            }
+            livesplit::run::saver::livesplit::save_run(&run, &mut lss)?;
+        }
+        write_splits(Path::new(split_file), &lss, &mut self.file_mtime)?;
+        timer.mark_as_unmodified();
+        if let Some((_, mtime)) = self.pending_reload.as_mut() {
+            *mtime = self.file_mtime;
+        }
+        self.last_saved = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Runs before shplit quits on a signal, keeping an attempt in progress if
+    /// `save_on_signal` is on.
+    fn save_before_exit(&mut self) {
+        let in_attempt = self.timer.as_ref().is_some_and(|timer| {
+            !self.stopwatch && timer.current_phase() != livesplit::TimerPhase::NotRunning
+        });
+        if self.config.save_on_signal && in_attempt {
+            self.save_run().ok();
+        }
+    }
+
+    fn save(&mut self) {
+        if let Err(err) = self.save_run() {
+            self.set_status(format!("couldn't save: {err}"));
+        }
+    }
+
+    fn split(&mut self) {
+        if self.stopwatch {
+            self.lap();
+            return;
+        }
+        if self.config.keep_running_after_last_split && self.on_last_split() {
+            let stop = keybinds::display(&self.config.keybindings.stop);
+            self.set_status(format!("all splits done, press {stop} to stop the timer"));
+            return;
+        }
+        if self.segment_too_short() {
+            return;
+        }
+        self.record_split();
+    }
+
+    /// Whether the current segment hasn't been running for `min_segment_secs` yet.
+    fn segment_too_short(&self) -> bool {
+        let Some(ref timer) = self.timer else {
+            return false;
+        };
+        let Some(i) = timer.current_split_index() else {
+            return false;
+        };
+        if self.config.min_segment_secs <= 0.0 || !timer.current_phase().is_running() {
+            return false;
+        }
+        let method = livesplit::TimingMethod::RealTime;
+        livesplit::analysis::live_segment_time(&timer.snapshot(), i, method)
+            .is_some_and(|time| time.total_seconds() < self.config.min_segment_secs)
+    }
+
+    /// Ends the run from the last split. Splitting does the same unless
+    /// `keep_running_after_last_split` is on.
+    fn stop(&mut self) {
+        if self.stopwatch {
+            if let Some(ref mut timer) = self.timer {
+                timer.split();
+            }
+            return;
+        }
+        if self.on_last_split() {
+            self.record_split();
+        }
+    }
+
+    /// Starts a plain count-up timer on a stand-in run, for timing without a splits file.
+    fn start_stopwatch(&mut self) {
+        // starting over is fine, a loaded run is left alone
+        if self.timer.is_some() && !self.stopwatch {
+            return;
+        }
+        let mut run = livesplit::Run::new();
+        run.set_game_name("Stopwatch");
+        run.push_segment(livesplit::Segment::new("Stopwatch"));
+        let mut timer = livesplit::Timer::new(run).unwrap();
+        timer.start();
+        self.timer = Some(timer);
+        self.config.split_file = None;
+        self.stopwatch = true;
+        self.laps.clear();
+        self.table_cursor = None;
+        self.table_scroll = None;
+    }
+
+    fn lap(&mut self) {
+        let Some(ref timer) = self.timer else { return };
+        if !timer.current_phase().is_running() {
+            return;
+        }
+        if let Some(time) = timer.snapshot().current_time().real_time {
+            self.laps.push(time);
+        }
+    }
+
+    fn on_last_split(&self) -> bool {
+        self.timer.as_ref().is_some_and(|timer| {
+            timer.current_phase().is_running()
+                && timer.current_split_index() == Some(timer.run().len() - 1)
+        })
+    }
+
+    fn record_split(&mut self) {
+        // the next attempt starts on the splits reloaded during the last one
+        if self
+            .timer
+            .as_ref()
+            .is_some_and(|timer| timer.current_phase() == livesplit::TimerPhase::NotRunning)
+        {
+            self.apply_pending_reload();
+        }
+        let Some(ref mut timer) = self.timer else {
+            return;
+        };
+        let split_index = timer.current_split_index();
+        let phase = timer.current_phase();
+        timer.split_or_start();
+        if let Some(i) = split_index.filter(|_| timer.current_split_index() != split_index) {
+            self.last_split = Some((i, Instant::now()));
+        }
+        if timer.current_split_index() != split_index {
+            // the attempt's start and then one per split
+            let index = timer.current_split_index().unwrap_or(0);
+            self.split_clock.truncate(index);
+            let now = livesplit::AtomicDateTime::now().time;
+            self.split_clock.push(now.to_offset(self.local_offset));
+        }
+        if phase == timer.current_phase() {
+            return;
+        }
+        match timer.current_phase() {
+            livesplit::TimerPhase::Running => self.daily.record_attempt(self.local_offset),
+            livesplit::TimerPhase::Ended => {
+                let time = timer.snapshot().current_time();
+                self.daily.record_finish(time, self.local_offset);
+            }
+            _ => return,
+        }
+        let ended = timer.current_phase() == livesplit::TimerPhase::Ended;
+        self.save_daily();
+        if ended && self.pending_reload.is_some() {
+            let reload = keybinds::display(&self.config.keybindings.reload_run);
+            self.set_status(format!(
+                "finished, press {reload} to switch to the reloaded splits"
+            ));
+        }
+        if self.config.autosave && ended {
+            self.save();
+        }
+    }
+
+    fn save_daily(&self) {
+        if let Some(ref split_file) = self.config.split_file {
+            self.daily.save(Path::new(split_file)).ok();
+        }
+    }
+
+    /// Steps through the run's comparisons, remembering the choice for this file
+    /// if there is one.
+    fn cycle_comparison(&mut self, forward: bool) {
+        let Some(ref mut timer) = self.timer else {
+            return;
+        };
+        match forward {
+            true => timer.switch_to_next_comparison(),
+            false => timer.switch_to_previous_comparison(),
+        }
+        if let Some(ref split_file) = self.config.split_file {
+            self.file_states.get_mut(split_file).comparison =
+                Some(timer.current_comparison().to_string());
+            self.file_states.save().ok();
+        }
+    }
+
+    fn toggle_timing_method(&mut self) {
+        let Some(ref mut timer) = self.timer else {
+            return;
+        };
+        timer.toggle_timing_method();
+        if let Some(ref split_file) = self.config.split_file {
+            self.file_states.get_mut(split_file).timing_method =
+                Some(timer.current_timing_method());
+            self.file_states.save().ok();
+        }
+    }
+
+    /// Applies the next configured preset's timing method and comparison.
+    fn cycle_preset(&mut self) {
+        let Some(ref mut timer) = self.timer else {
+            return;
+        };
+        if self.config.presets.is_empty() {
+            self.set_status("there are no presets in the config");
+            return;
+        }
+        let next = self
+            .preset
+            .map_or(0, |preset| (preset + 1) % self.config.presets.len());
+        let preset = &self.config.presets[next];
+        self.preset = Some(next);
+        if let Some(method) = preset.timing_method {
+            timer.set_current_timing_method(method);
+        }
+        let missing = match &preset.comparison {
+            Some(comparison) => timer.set_current_comparison(comparison.as_str()).is_err(),
+            None => false,
+        };
+        if let Some(ref split_file) = self.config.split_file {
+            let state = self.file_states.get_mut(split_file);
+            state.timing_method = Some(timer.current_timing_method());
+            state.comparison = Some(timer.current_comparison().to_string());
+            self.file_states.save().ok();
+        }
+        let status = match missing {
+            true => format!(
+                "{}: this run has no comparison called {:?}",
+                preset.name,
+                preset.comparison.as_deref().unwrap_or_default()
+            ),
+            false => preset.name.clone(),
+        };
+        self.set_status(status);
+    }
+
+    /// Switches the table between real and game time, leaving the clock alone.
+    fn toggle_table_timing_method(&mut self) {
+        let Some(ref timer) = self.timer else {
+            return;
+        };
+        let current = self.table_method.unwrap_or(timer.current_timing_method());
+        let next = match current {
+            livesplit::TimingMethod::RealTime => livesplit::TimingMethod::GameTime,
+            livesplit::TimingMethod::GameTime => livesplit::TimingMethod::RealTime,
+        };
+        self.table_method = Some(next);
+    }
+
+    /// Stops or restarts game time by hand, for removing loads without an autosplitter.
+    fn toggle_game_time_freeze(&mut self) {
+        let Some(ref mut timer) = self.timer else {
+            return;
+        };
+        if !(timer.current_phase().is_running() || timer.current_phase().is_paused()) {
+            return;
+        }
+        if !timer.is_game_time_initialized() {
+            timer.initialize_game_time();
+        }
+        match timer.is_game_time_paused() {
+            true => timer.resume_game_time(),
+            false => timer.pause_game_time(),
+        }
+    }
+
+    /// Undoes up to `rewind_splits` splits, so a section can be replayed without a reset.
+    fn rewind(&mut self) {
+        if let Some(ref mut timer) = self.timer {
+            for _ in 0..self.config.rewind_splits {
+                timer.undo_split();
+            }
+            let current = timer.current_split_index().unwrap_or(0);
+            self.marked_golds.retain(|&(i, _, _)| i < current);
+            self.split_clock.truncate(current + 1);
+        }
+        self.last_split = None;
+    }
+
+    /// Makes the segment that was just split the best segment, even if it isn't
+    /// faster, e.g. to keep a segment from practice. Written out on the next save.
+    fn mark_gold(&mut self) {
+        let Some(ref timer) = self.timer else { return };
+        let Some(i) = timer.current_split_index().and_then(|i| i.checked_sub(1)) else {
+            return;
+        };
+        let method = timer.current_timing_method();
+        let segments = timer.run().segments();
+        let Some(split) = segments[i].split_time()[method] else {
+            self.set_status("the last segment was skipped, there's no time to keep");
+            return;
+        };
+        let previous = match i.checked_sub(1) {
+            Some(previous) => segments[previous].split_time()[method],
+            None => Some(livesplit::TimeSpan::zero()),
+        };
+        let Some(previous) = previous else {
+            self.set_status(
+                "the split before was skipped, so the time covers more than this segment",
+            );
+            return;
+        };
+        let name = segments[i].name().to_string();
+        self.marked_golds
+            .retain(|&(marked, marked_method, _)| (marked, marked_method) != (i, method));
+        self.marked_golds.push((i, method, split - previous));
+        // saving now would record the attempt in progress as a reset
+        let save = keybinds::display(&self.config.keybindings.save);
+        self.set_status(format!(
+            "{name} becomes a gold the next time the splits are saved ({save})"
+        ));
+    }
+
+    /// Opens the note prompt for the running attempt, or the last finished one.
+    fn edit_note(&mut self) {
+        let Some(ref timer) = self.timer else { return };
+        let last = timer
+            .run()
+            .attempt_history()
+            .iter()
+            .map(livesplit::Attempt::index)
+            .max();
+        let attempt = match timer.current_phase() {
+            livesplit::TimerPhase::NotRunning => match last {
+                Some(index) => index,
+                None => return,
+            },
+            // the index the attempt will get once it's added to the history
+            _ => last.unwrap_or(0).max(0) + 1,
+        };
+        self.prompt = Some(Prompt {
+            kind: PromptKind::Note(attempt),
+            text: self.notes.get(attempt).unwrap_or_default().to_string(),
+        });
+    }
+
+    fn handle_library_key(&mut self, key: KeyEvent) {
+        let Some(ref mut library) = self.library else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.library = None,
+            KeyCode::Up | KeyCode::Char('k') => library.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => library.select_next(),
+            KeyCode::Enter => {
+                if let Some(path) = library.selected().map(Path::to_path_buf) {
+                    self.library = None;
+                    self.open_run(&path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_prompt_key(&mut self, key: KeyEvent) {
+        let Some(ref mut prompt) = self.prompt else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                if matches!(prompt.kind, PromptKind::Category(_)) {
+                    self.library = Some(Library::new(&self.config));
+                }
+                self.prompt = None;
+            }
+            KeyCode::Enter => {
+                if let Some(prompt) = self.prompt.take() {
+                    self.submit_prompt(prompt);
+                }
+            }
+            KeyCode::Backspace => {
+                prompt.text.pop();
+            }
+            KeyCode::Char(c) if (key.modifiers - KeyModifiers::SHIFT).is_empty() => {
+                prompt.text.push(c)
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether a key quits even with a prompt open: Ctrl+C, or the quit binding as long
+    /// as it takes a modifier, so plain letters can still be typed.
+    fn quits_from_prompt(&self, key: &KeyEvent) -> bool {
+        let ctrl_c = KeyBind {
+            code: KeyCode::Char('c'),
+            modifiers: KeyModifiers::CONTROL,
+        };
+        let modified = key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        ctrl_c.matches(key)
+            || modified && self.config.keybindings().resolve(&[*key]) == Chord::Action(Action::Quit)
+    }
+
+    fn submit_prompt(&mut self, prompt: Prompt) {
+        match prompt.kind {
+            PromptKind::Note(attempt) => {
+                self.notes.set(attempt, &prompt.text);
+                if let Some(ref split_file) = self.config.split_file {
+                    self.notes.save(Path::new(split_file)).ok();
+                }
+            }
+            PromptKind::SplitTimes(times) => self.submit_split_time(times, prompt.text),
+            PromptKind::Target => match parse_time(&prompt.text) {
+                Some(target) => self.target = Some(target),
+                None if prompt.text.trim().is_empty() => self.target = None,
+                None => {
+                    self.set_status(format!("couldn't read {:?} as a time", prompt.text.trim()))
+                }
+            },
+            PromptKind::Convert(_) => self.convert(prompt.text.trim(), false),
+            PromptKind::Category(category) => {
+                let info = keybinds::display(&self.config.keybindings.info);
+                self.set_status(format!("timing {category}, press {info} for details"));
+            }
+            PromptKind::Overwrite(path) => match prompt.text.trim() {
+                "yes" => self.convert(&path, true),
+                _ => self.set_status("nothing was converted"),
+            },
+            PromptKind::ImportGolds => self.import_golds(Path::new(prompt.text.trim())),
+            PromptKind::Confirm(maintenance) => match prompt.text.trim() {
+                "yes" => self.run_maintenance(maintenance),
+                _ => self.set_status("nothing was changed"),
+            },
+            PromptKind::RaceFile => self.pick_race_comparison(Path::new(prompt.text.trim())),
+            PromptKind::RaceComparison(other, name) => {
+                self.import_race(&other, prompt.text.trim(), &name)
+            }
+            PromptKind::StartAt => match parse_time(&prompt.text) {
+                Some(time) => self.start_at(time),
+                None => {
+                    self.set_status(format!("couldn't read {:?} as a time", prompt.text.trim()))
+                }
+            },
+        }
+    }
+
+    fn confirm_maintenance(&mut self, maintenance: Maintenance) {
+        let Some(ref timer) = self.timer else { return };
+        if self.config.split_file.is_none() {
+            self.set_status("there's no splits file to back up first");
+            return;
+        }
+        if timer.current_phase() != livesplit::TimerPhase::NotRunning {
+            self.set_status(format!(
+                "can't {} during an attempt",
+                maintenance.describe()
+            ));
+            return;
+        }
+        self.prompt = Some(Prompt {
+            kind: PromptKind::Confirm(maintenance),
+            text: String::new(),
+        });
+    }
+
+    /// Backs the run up, then cleans it up and saves it. Nothing changes if the backup fails.
+    fn run_maintenance(&mut self, maintenance: Maintenance) {
+        let (Some(timer), Some(split_file)) = (&mut self.timer, &self.config.split_file) else {
+            return;
+        };
+        let backup = match maintenance::backup(Path::new(split_file)) {
+            Ok(backup) => backup,
+            Err(err) => {
+                self.set_status(format!(
+                    "couldn't write a backup, nothing was changed: {err}"
+                ));
+                return;
+            }
+        };
+        let mut run = timer.run().clone();
+        for &(i, method, time) in &self.marked_golds {
+            run.segment_mut(i).best_segment_time_mut()[method] = Some(time);
+        }
+        maintenance.apply(&mut run);
+        timer.set_run(run).ok();
+        self.marked_golds.clear();
+        match self.save_run() {
+            Ok(()) => self.set_status(format!("done, the old file is at {}", backup.display())),
+            Err(err) => self.set_status(format!("couldn't save: {err}")),
+        }
+    }
+
+    /// Asks for the next frame to be saved next to the splits file, or in the
+    /// current directory without one.
+    fn request_snapshot(&mut self) {
+        #[cfg(feature = "snapshot")]
+        {
+            self.snapshot_requested = true;
+        }
+        #[cfg(not(feature = "snapshot"))]
+        self.set_status("snapshots need shplit built with the snapshot feature");
+    }
+
+    #[cfg(feature = "snapshot")]
+    fn save_snapshot(&mut self, buffer: &tui::buffer::Buffer) {
+        self.snapshot_requested = false;
+        let path = match &self.config.split_file {
+            Some(split_file) => PathBuf::from(split_file).with_extension("svg"),
+            None => PathBuf::from("shplit.svg"),
+        };
+        match snapshot::save(buffer, &path) {
+            Ok(()) => self.set_status(format!("snapshot saved to {}", path.display())),
+            Err(err) => self.set_status(format!("couldn't save the snapshot: {err}")),
+        }
+    }
+
+    /// Writes the run's golds next to the splits file for sharing.
+    fn export_golds(&mut self) {
+        let (Some(timer), Some(split_file)) = (&self.timer, &self.config.split_file) else {
+            return;
+        };
+        let path = SharedGolds::path_for(Path::new(split_file));
+        match SharedGolds::from_run(timer.run()).save(&path) {
+            Ok(()) => self.set_status(format!("golds written to {}", path.display())),
+            Err(err) => self.set_status(format!("couldn't export golds: {err}")),
+        }
+    }
+
+    fn edit_golds_import(&mut self) {
+        let Some(ref timer) = self.timer else { return };
+        if timer.current_phase() != livesplit::TimerPhase::NotRunning {
+            self.set_status("can't import golds during an attempt");
+            return;
+        }
+        self.prompt = Some(Prompt {
+            kind: PromptKind::ImportGolds,
+            text: String::new(),
+        });
+    }
+
+    /// Adds someone's shared golds to the run as a comparison and switches to it.
+    fn import_golds(&mut self, path: &Path) {
+        let Some(ref mut timer) = self.timer else {
+            return;
+        };
+        if timer.current_phase() != livesplit::TimerPhase::NotRunning {
+            self.set_status("can't import golds during an attempt");
+            return;
+        }
+        let golds = match SharedGolds::load(path) {
+            Ok(golds) => golds,
+            Err(err) => {
+                self.set_status(format!("couldn't read golds: {err}"));
+                return;
+            }
+        };
+        let mut run = timer.run().clone();
+        let mismatched = match golds.add_to(&mut run) {
+            Ok(mismatched) => mismatched,
+            Err(err) => {
+                self.set_status(format!("couldn't import golds: {err}"));
+                return;
+            }
+        };
+        timer.set_run(run).ok();
+        timer.set_current_comparison(golds::COMPARISON).ok();
+        match mismatched {
+            0 => self.set_status(format!("imported golds as {:?}", golds::COMPARISON)),
+            n => self.set_status(format!(
+                "imported golds as {:?}, {n} segment names differ",
+                golds::COMPARISON
+            )),
+        }
+    }
+
+    fn edit_race_import(&mut self) {
+        let Some(ref timer) = self.timer else { return };
+        if timer.current_phase() != livesplit::TimerPhase::NotRunning {
+            self.set_status("can't import a comparison during an attempt");
+            return;
+        }
+        self.prompt = Some(Prompt {
+            kind: PromptKind::RaceFile,
+            text: String::new(),
+        });
+    }
+
+    /// Reads the other splits file and asks which of its comparisons to take.
+    fn pick_race_comparison(&mut self, path: &Path) {
+        let parsed = match std::fs::read(path) {
+            Ok(bytes) => load::parse_run(&bytes, Some(path)),
+            Err(err) => Err(err.to_string()),
+        };
+        let mut other = match parsed {
+            Ok(parsed) => parsed.run,
+            Err(err) => {
+                self.set_status(format!("couldn't load {}: {err}", path.display()));
+                return;
+            }
+        };
+        // the generated comparisons aren't filled in until asked for
+        other.regenerate_comparisons();
+        let name = path.file_stem().map_or(String::from("Race"), |stem| {
+            stem.to_string_lossy().into_owned()
+        });
+        self.prompt = Some(Prompt {
+            kind: PromptKind::RaceComparison(Box::new(other), name),
+            text: String::from(livesplit::comparison::personal_best::NAME),
+        });
+    }
+
+    /// Adds a comparison from another run and switches to it, for racing their splits.
+    fn import_race(&mut self, other: &livesplit::Run, comparison: &str, name: &str) {
+        let Some(ref mut timer) = self.timer else {
+            return;
+        };
+        if timer.current_phase() != livesplit::TimerPhase::NotRunning {
+            self.set_status("can't import a comparison during an attempt");
+            return;
+        }
+        let mut run = timer.run().clone();
+        let unmatched = match race::import_comparison(&mut run, other, comparison, name) {
+            Ok(unmatched) => unmatched,
+            Err(err) => {
+                self.set_status(format!("couldn't import the comparison: {err}"));
+                return;
+            }
+        };
+        timer.set_run(run).ok();
+        timer.set_current_comparison(name).ok();
+        match unmatched.len() {
+            0 => self.set_status(format!("imported {comparison:?} as {name:?}")),
+            n => self.set_status(format!(
+                "imported {comparison:?} as {name:?}, {n} segments had no match: {}",
+                unmatched.join(", ")
+            )),
+        }
+    }
+
+    /// Saves the loaded run as a LiveSplit file and switches over to it, asking
+    /// first if that means overwriting a file.
+    fn convert(&mut self, path: &str, overwrite: bool) {
+        if !overwrite && Path::new(path).exists() {
+            self.prompt = Some(Prompt {
+                kind: PromptKind::Overwrite(path.to_string()),
+                text: String::new(),
+            });
+            return;
+        }
+        let previous = self.config.split_file.replace(path.to_string());
+        // still catches the file changing after the overwrite was confirmed
+        let previous_mtime =
+            std::mem::replace(&mut self.file_mtime, modified_time(Path::new(path)));
+        match self.save_run() {
+            Ok(()) => {
+                self.notes = AttemptNotes::default();
+                self.daily.save(Path::new(path)).ok();
+                self.config.save().ok();
+            }
+            Err(err) => {
+                self.config.split_file = previous;
+                self.file_mtime = previous_mtime;
+                self.set_status(format!("couldn't convert: {err}"));
+            }
+        }
+    }
+
+    /// Saves the run to a new file and carries on with that one, leaving the
+    /// original as it was, e.g. for branching a route into a new category.
+    fn save_copy(&mut self, path: &Path) {
+        let path = path.to_string_lossy().into_owned();
+        if self.config.split_file.as_deref() == Some(path.as_str()) {
+            self.save();
+            return;
+        }
+        let previous = self.config.split_file.replace(path.clone());
+        let previous_mtime = self.file_mtime.take();
+        match self.save_run() {
+            Ok(()) => {
+                // notes go along with the attempt history they're about
+                self.notes.save(Path::new(&path)).ok();
+                self.daily = DailyStats::default();
+                self.config.save().ok();
+                self.set_status(format!("saved a copy to {path}"));
+            }
+            Err(err) => {
+                self.config.split_file = previous;
+                self.file_mtime = previous_mtime;
+                self.set_status(format!("couldn't save a copy: {err}"));
+            }
+        }
+    }
+
+    fn edit_start_time(&mut self) {
+        let Some(ref timer) = self.timer else { return };
+        if timer.current_phase() != livesplit::TimerPhase::NotRunning {
+            self.set_status("the clock is already running");
+            return;
+        }
+        self.prompt = Some(Prompt {
+            kind: PromptKind::StartAt,
+            text: String::new(),
+        });
+    }
+
+    /// Starts the attempt with the clock already at `time`. The timer only knows
+    /// how to start from the run's start offset, so that's swapped out for the
+    /// attempt, and the file keeps its own when saved.
+    fn start_at(&mut self, time: livesplit::TimeSpan) {
+        let Some(ref mut timer) = self.timer else {
+            return;
+        };
+        if timer.current_phase() != livesplit::TimerPhase::NotRunning {
+            return;
+        }
+        let mut run = timer.run().clone();
+        let offset = self.file_offset.unwrap_or(run.offset());
+        run.set_offset(time);
+        if timer.set_run(run).is_err() {
+            return;
+        }
+        self.file_offset = Some(offset);
+        self.record_split();
+        self.set_status(format!(
+            "started from {}",
+            format_time(time, self.config.rounding)
+        ));
+    }
+
+    fn edit_target(&mut self) {
+        if self.timer.is_some() {
+            self.prompt = Some(Prompt {
+                kind: PromptKind::Target,
+                text: self
+                    .target
+                    .map(|target| format_time(target, self.config.rounding))
+                    .unwrap_or_default(),
+            });
+        }
+    }
+
+    fn enter_times(&mut self) {
+        let Some(ref timer) = self.timer else { return };
+        if timer.current_phase() != livesplit::TimerPhase::NotRunning {
+            self.set_status("can't enter times during an attempt");
+            return;
+        }
+        self.prompt = Some(Prompt {
+            kind: PromptKind::SplitTimes(Vec::new()),
+            text: String::new(),
+        });
+    }
+
+    /// Takes the next segment's split time, an empty entry skipping the segment,
+    /// and logs the attempt once every segment has been entered.
+    fn submit_split_time(&mut self, mut times: Vec<Option<livesplit::TimeSpan>>, text: String) {
+        let Some(ref mut timer) = self.timer else {
+            return;
+        };
+        let time = match parse_time(&text) {
+            Some(time) => Some(time),
+            None if text.trim().is_empty() => None,
+            None => {
+                self.set_status(format!("couldn't read {:?} as a time", text.trim()));
+                self.prompt = Some(Prompt {
+                    kind: PromptKind::SplitTimes(times),
+                    text,
+                });
+                return;
+            }
+        };
+        let previous = times.iter().flatten().last();
+        if let (Some(time), Some(previous)) = (time, previous) {
+            if time <= *previous {
+                self.set_status("split times have to keep increasing");
+                self.prompt = Some(Prompt {
+                    kind: PromptKind::SplitTimes(times),
+                    text,
+                });
+                return;
+            }
+        }
+        times.push(time);
+        if times.len() < timer.run().len() {
+            self.prompt = Some(Prompt {
+                kind: PromptKind::SplitTimes(times),
+                text: String::new(),
+            });
+            return;
+        }
+        let mut run = timer.run().clone();
+        manual::log_attempt(&mut run, &times, timer.current_timing_method());
+        timer.set_run(run).ok();
+        self.daily.record_attempt(self.local_offset);
+        if let Some(&Some(time)) = times.last() {
+            let time = livesplit::Time::new()
+                .with_timing_method(timer.current_timing_method(), Some(time));
+            self.daily.record_finish(time, self.local_offset);
+        }
+        self.save_daily();
+        self.save();
+    }
+}
+
+#[derive(Debug, Default)]
+struct Args {
+    profile: Option<String>,
+    /// Read the splits from stdin, given as `-`. Only then, since launchers and
+    /// services can hand over a stdin that never closes.
+    stdin: bool,
+    /// LiveSplit settings to copy the hotkeys from, instead of starting.
+    import_hotkeys: Option<PathBuf>,
+    /// Run headless on global hotkeys, serving the timer to display clients.
+    daemon: bool,
+    /// Display the timer of a running daemon.
+    attach: bool,
+    /// Write the attempt history of the configured splits file to this database.
+    #[cfg(feature = "sqlite-export")]
+    export_sqlite: Option<PathBuf>,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut args = Self::default();
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-" => {
+                    args.stdin = true;
+                    continue;
+                }
+                "--daemon" => {
+                    args.daemon = true;
+                    continue;
+                }
+                "--attach" => {
+                    args.attach = true;
+                    continue;
+                }
+                _ => (),
+            }
+            if let Some(path) = flag_value(&arg, "--import-hotkeys", &mut iter) {
+                match path {
+                    Some(path) if !path.is_empty() => args.import_hotkeys = Some(path.into()),
+                    _ => {
+                        return Err(String::from(
+                            "--import-hotkeys needs the path to LiveSplit's settings.cfg",
+                        ))
+                    }
+                }
+                continue;
+            }
+            #[cfg(feature = "sqlite-export")]
+            if let Some(path) = flag_value(&arg, "--export-sqlite", &mut iter) {
+                match path {
+                    Some(path) if !path.is_empty() => args.export_sqlite = Some(path.into()),
+                    _ => {
+                        return Err(String::from(
+                            "--export-sqlite needs the path of the database to write",
+                        ))
+                    }
+                }
+                continue;
+            }
+            let Some(profile) = flag_value(&arg, "--profile", &mut iter) else {
+                return Err(format!("unknown argument {arg:?}"));
+            };
+            match profile {
+                Some(name) if !name.is_empty() && !name.contains(['/', '\\']) => {
+                    args.profile = Some(name)
+                }
+                _ => {
+                    return Err(String::from(
+                        "--profile needs a name, e.g. --profile stream",
+                    ))
+                }
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// The value given to `flag` as `--flag value` or `--flag=value`, or `None` if `arg` isn't `flag`.
+fn flag_value(
+    arg: &str,
+    flag: &str,
+    rest: &mut impl Iterator<Item = String>,
+) -> Option<Option<String>> {
+    match arg.strip_prefix(flag)? {
+        "" => Some(rest.next()),
+        value => value.strip_prefix('=').map(|value| Some(value.to_string())),
+    }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse()?;
+    if let Some(profile) = args.profile {
+        set_profile(profile);
+    }
+
+    if let Some(path) = args.import_hotkeys {
+        let settings = std::fs::read_to_string(&path)
+            .map_err(|err| format!("couldn't read {}: {err}", path.display()))?;
+        let mut config = Config::load().unwrap_or_default();
+        let imported = import::livesplit_hotkeys(&settings, &mut config.keybindings);
+        if imported.is_empty() {
+            return Err(format!("no hotkeys shplit supports in {}", path.display()).into());
+        }
+        // the legacy key would otherwise win over an imported split binding that's the default
+        let dropped = match imported.contains(&"split") {
+            true => config.split_key.take(),
+            false => None,
+        };
+        config.save()?;
+        println!("imported hotkeys for {}", imported.join(", "));
+        if let Some(split_key) = dropped {
+            println!("dropped split_key = {split_key:?}, the imported split key replaces it");
+        }
+        return Ok(());
+    }
+    #[cfg(feature = "sqlite-export")]
+    if let Some(db) = args.export_sqlite {
+        let config = Config::load().unwrap_or_default();
+        let split_file = config
+            .split_file
+            .ok_or("no splits file set in the config to export")?;
+        let bytes = std::fs::read(&split_file)
+            .map_err(|err| format!("couldn't read {split_file}: {err}"))?;
+        let parsed = load::parse_run(&bytes, Some(Path::new(&split_file)))?;
+        sqlite::export(&parsed.run, &db)
+            .map_err(|err| format!("couldn't export to {}: {err}", db.display()))?;
+        println!(
+            "exported {} attempts to {}",
+            parsed.run.attempt_history().len(),
+            db.display()
+        );
+        return Ok(());
+    }
+    if args.daemon {
+        return daemon::run(Config::load().unwrap_or_default());
+    }
+    if args.attach {
+        return client::run(Config::load().unwrap_or_default());
+    }
+
+    let mut app = App::default();
+    if args.stdin {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        app.load_stdin(&bytes)
+            .map_err(|err| format!("couldn't load splits from stdin: {err}"))?;
+    }
+    let alternate_screen = app.config.alternate_screen;
+
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(
-        stdout,
-        EnterAlternateScreen,
-        EnableMouseCapture,
-        EnableBracketedPaste
-    )?;
+    // fall back to rendering inline rather than not starting at all
+    let alternate_screen = alternate_screen
+        && match std::env::var("TERM").as_deref() {
+            Ok("dumb") => {
+                app.set_status("this terminal has no alternate screen, rendering inline");
+                false
+            }
+            _ => match execute!(stdout, EnterAlternateScreen) {
+                Ok(()) => true,
+                Err(err) => {
+                    app.set_status(format!(
+                        "couldn't switch to the alternate screen ({err}), rendering inline"
+                    ));
+                    false
+                }
+            },
+        };
+    execute!(stdout, EnableMouseCapture, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let viewport = match alternate_screen {
+        true => Viewport::Fullscreen,
+        false => Viewport::Inline(app.inline_height()),
+    };
+    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })?;
+
+    watch_exit_signals(&app.terminate);
 
-    // 30 frames per second. todo: make it configurable
-    let tick_rate = Duration::from_secs_f32(1.0 / 30.0);
-    let app = App::default();
-    let res = run_app(&mut terminal, app, tick_rate);
+    let res = run_app(&mut terminal, app);
 
     // restore terminal
     disable_raw_mode()?;
+    if alternate_screen {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    }
     execute!(
         terminal.backend_mut(),
-        LeaveAlternateScreen,
         DisableMouseCapture,
         DisableBracketedPaste
     )?;
+    terminal.show_cursor()?;
 
     if let Err(err) = res {
         println!("{err:?}");
@@ -87,58 +1693,150 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn run_app<B: Backend>(
-    terminal: &mut Terminal<B>,
-    mut app: App,
-    tick_rate: Duration,
-) -> io::Result<()> {
+fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
     let mut last_tick = Instant::now();
+    let mut last_redraw = Instant::now();
     loop {
-        terminal.draw(|f| ui(f, &mut app))?;
+        if app.terminate.load(Ordering::Relaxed) {
+            app.save_before_exit();
+            return Ok(());
+        }
+        // read every frame so a config reload takes effect right away
+        let tick_rate = app.config.frame_time();
+        #[cfg(feature = "audio-cue")]
+        app.poll_audio();
+        app.sync_table_state();
+        let redraw_secs = app.config.redraw_secs;
+        if redraw_secs > 0.0 && last_redraw.elapsed().as_secs_f64() >= redraw_secs {
+            terminal.clear()?;
+            last_redraw = Instant::now();
+        }
+        let _frame = terminal.draw(|f| ui::ui(f, &mut app))?;
+        #[cfg(feature = "snapshot")]
+        if app.snapshot_requested {
+            app.save_snapshot(_frame.buffer);
+        }
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
         if crossterm::event::poll(timeout)? {
             match event::read()? {
-                Event::Key(key) => {
-                    if key.kind == KeyEventKind::Press {
-                        match key.code {
-                            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                return Ok(())
-                            }
-                            KeyCode::Char(' ') => {
-                                if let Some(ref mut timer) = app.timer {
-                                    timer.split_or_start()
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    if app.prompt.is_some() {
+                        if app.quits_from_prompt(&key) {
+                            return Ok(());
+                        }
+                        app.handle_prompt_key(key);
+                        continue;
+                    }
+                    if app.library.is_some() {
+                        app.handle_library_key(key);
+                        continue;
+                    }
+                    if app.big_timer {
+                        app.big_timer = false;
+                        continue;
+                    }
+                    let Some(action) = app.action_for(key) else {
+                        continue;
+                    };
+                    match action {
+                        Action::Quit => return Ok(()),
+                        // drawing only what changed can't fix a screen that got scrolled
+                        Action::Redraw => terminal.clear()?,
+                        Action::Split => app.split(),
+                        Action::Open => {
+                            use nfde::*;
+                            let Ok(file_dialog) = Nfd::new() else {
+                                continue;
+                            };
+                            let res = file_dialog
+                                .open_file()
+                                .add_filter("LiveSplit file", "lss")
+                                .unwrap()
+                                .show();
+
+                            match res {
+                                DialogResult::Ok(path) => {
+                                    app.open_run(path.as_path());
                                 }
+                                _ => continue,
                             }
-                            KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                use nfde::*;
-                                let Ok(file_dialog) = Nfd::new() else { continue };
-                                let res = file_dialog
-                                    .open_file()
-                                    .add_filter("LiveSplit file", "lss")
-                                    .unwrap()
-                                    .show();
-
-                                match res {
-                                    DialogResult::Ok(path) => {
-                                        if path.try_exists().ok() == Some(true) {
-                                            app.load_run(path.as_path()).ok();
-                                            app.config.save().unwrap();
-                                        }
-                                    }
-                                    _ => continue,
-                                }
+                        }
+                        Action::SaveCopy => {
+                            use nfde::*;
+                            let Ok(file_dialog) = Nfd::new() else {
+                                continue;
+                            };
+                            let res = file_dialog
+                                .save_file()
+                                .add_filter("LiveSplit file", "lss")
+                                .unwrap()
+                                .show();
+                            if let DialogResult::Ok(path) = res {
+                                app.save_copy(path.as_path());
                             }
-                            _ => {}
                         }
+                        Action::Rewind => app.rewind(),
+                        Action::Note => app.edit_note(),
+                        Action::History => app.show_history = !app.show_history,
+                        Action::Info => app.show_info = !app.show_info,
+                        Action::BigTimer => app.big_timer = app.timer.is_some(),
+                        Action::Save => app.save(),
+                        Action::EnterTimes => app.enter_times(),
+                        Action::NextComparison => app.cycle_comparison(true),
+                        Action::NextPreset => app.cycle_preset(),
+                        Action::PreviousComparison => app.cycle_comparison(false),
+                        Action::ToggleTimingMethod => app.toggle_timing_method(),
+                        Action::ToggleTableTimingMethod => app.toggle_table_timing_method(),
+                        Action::SetTarget => app.edit_target(),
+                        Action::StartAt => app.edit_start_time(),
+                        Action::FreezeGameTime => app.toggle_game_time_freeze(),
+                        Action::Stop => app.stop(),
+                        Action::MarkGold => app.mark_gold(),
+                        Action::Stopwatch => app.start_stopwatch(),
+                        Action::Library => app.library = Some(Library::new(&app.config)),
+                        Action::ExportGolds => app.export_golds(),
+                        Action::Snapshot => app.request_snapshot(),
+                        Action::ImportGolds => app.edit_golds_import(),
+                        Action::ImportComparison => app.edit_race_import(),
+                        Action::ClearHistory => app.confirm_maintenance(Maintenance::ClearHistory),
+                        Action::ResetGolds => app.confirm_maintenance(Maintenance::ResetGolds),
+                        Action::CursorUp => app.move_cursor(false),
+                        Action::CursorDown => app.move_cursor(true),
+                        Action::ToggleDelta => {
+                            app.config.hide_delta = !app.config.hide_delta;
+                            app.config.save().ok();
+                        }
+                        Action::ReloadConfig => app.reload_config(),
+                        Action::ReloadRun => app.reload_run(),
+                    }
+                }
+                Event::Mouse(mouse)
+                    if app.config.scroll_step > 0
+                        && app.library.is_none()
+                        && !app.big_timer
+                        && !app.stopwatch =>
+                {
+                    match mouse.kind {
+                        MouseEventKind::ScrollDown => app.scroll_table(true),
+                        MouseEventKind::ScrollUp => app.scroll_table(false),
+                        _ => (),
+                    }
+                }
+                Event::Paste(data) if app.prompt.is_some() => {
+                    if let Some(ref mut prompt) = app.prompt {
+                        prompt.text.push_str(data.trim_end());
                     }
                 }
                 Event::Paste(data) => {
                     let path = PathBuf::from(data);
-                    if path.try_exists().ok() == Some(true) {
-                        app.load_run(path).unwrap();
-                        app.config.save().ok();
+                    if path.try_exists().ok() != Some(true) {
+                        continue;
+                    }
+                    match path.to_string_lossy().ends_with(".golds.toml") {
+                        true => app.import_golds(&path),
+                        false => app.open_run(&path),
                     }
                 }
                 _ => (),
@@ -149,63 +1847,3 @@ fn run_app<B: Backend>(
         }
     }
 }
-
-fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
-    if app.timer.is_none() {
-        let block = Block::default()
-            .title("shplit")
-            .borders(Borders::ALL)
-            .title_alignment(Alignment::Center);
-        f.render_widget(
-            Paragraph::new("Drag and drop a splits file onto the window, or press CTRL + O.")
-                .block(block)
-                .alignment(Alignment::Center),
-            f.size(),
-        );
-        return;
-    }
-
-    let timer = app.timer.as_mut().unwrap();
-    let rects = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(100), Constraint::Min(1)].as_ref())
-        .split(f.size());
-    app.table_state.select(timer.current_split_index());
-
-    let header = Row::new(["Segment", "Time"]).height(1).bottom_margin(1);
-
-    let rows: Vec<Row> = timer
-        .run()
-        .segments()
-        .iter()
-        .map(|segment| {
-            let time = segment
-                .split_time()
-                .game_time
-                .map_or(String::from("0:00"), |time| time.to_duration().to_string());
-            Row::new([segment.name().to_string(), time])
-        })
-        .collect();
-
-    let table = Table::new(rows)
-        .header(header)
-        .block(Block::default().borders(Borders::ALL))
-        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
-        .widths(&[Constraint::Percentage(70), Constraint::Min(5)]);
-
-    f.render_stateful_widget(table, rects[0], &mut app.table_state);
-    // hhmmssxxx asf
-    let duration = timer.current_attempt_duration().to_duration();
-    let timer_text = format!(
-        "{:02}:{:02}:{:02}.{:03}",
-        duration.whole_hours(),
-        duration.whole_minutes() % 60,
-        duration.whole_seconds() % 60,
-        duration.subsec_milliseconds()
-    );
-    let paragraph = match timer.current_split().is_some() {
-        true => Paragraph::new(timer_text).bold(),
-        false => Paragraph::new(timer_text).slow_blink(),
-    };
-    f.render_widget(paragraph, rects[1]);
-}
@@ -0,0 +1,75 @@
+//! Destructive clean-ups of a splits file, which always leave a backup behind.
+
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use livesplit::{AtomicDateTime, Run, TimingMethod};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Maintenance {
+    /// Forget every attempt and segment time, keeping the PB and golds.
+    ClearHistory,
+    /// Recalculate the golds from the segment history, dropping ones that
+    /// were imported or edited in by hand.
+    ResetGolds,
+}
+
+impl Maintenance {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::ClearHistory => "clear the attempt history",
+            Self::ResetGolds => "reset the golds to the best times in the history",
+        }
+    }
+
+    pub fn apply(self, run: &mut Run) {
+        match self {
+            Self::ClearHistory => run.clear_history(),
+            Self::ResetGolds => reset_golds(run),
+        }
+        run.fix_splits();
+        run.regenerate_comparisons();
+    }
+}
+
+/// Copies the splits file next to itself with the current UTC time in the name,
+/// e.g. `any.backup-20240102-150405.lss`. The file is copied as it is, since the
+/// loaded run is missing whatever the parser didn't understand.
+pub fn backup(split_file: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let now = AtomicDateTime::now().time;
+    let stamp = format!(
+        "{}{:02}{:02}-{:02}{:02}{:02}",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+    let extension = split_file.extension().map_or(String::from("lss"), |ext| {
+        ext.to_string_lossy().into_owned()
+    });
+    let path = split_file.with_extension(format!("backup-{stamp}.{extension}"));
+    std::fs::copy(split_file, &path)?;
+    Ok(path)
+}
+
+/// Sets each gold to the fastest time in the segment's history. Fixing the
+/// splits afterwards brings back the PB's segment if that's faster still.
+fn reset_golds(run: &mut Run) {
+    for segment in run.segments_mut() {
+        for method in [TimingMethod::RealTime, TimingMethod::GameTime] {
+            let fastest = segment
+                .segment_history()
+                .iter()
+                .filter_map(|&(_, time)| time[method])
+                .min();
+            // without any history there's nothing better to go on
+            if fastest.is_some() {
+                segment.best_segment_time_mut()[method] = fastest;
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use livesplit::{run::Attempt, Run, Time, TimeSpan, Timer, TimerPhase, TimingMethod};
+
+/// Records an attempt that was timed elsewhere, doing the same bookkeeping the
+/// timer does on reset: attempt history, best segments, PB and segment history.
+/// `splits` holds the cumulative split time of each segment, `None` if skipped.
+pub fn log_attempt(run: &mut Run, splits: &[Option<TimeSpan>], method: TimingMethod) {
+    let splits: Vec<Time> = splits
+        .iter()
+        .map(|split| Time::new().with_timing_method(method, *split))
+        .collect();
+    let time = match splits.len() == run.len() {
+        true => splits.last().copied().unwrap_or_default(),
+        false => Time::default(),
+    };
+    let attempt = Attempt::new(0, time, None, None, None);
+    record_attempt(run, &splits, &attempt, method, run.len());
+}
+
+/// Records the timer's attempt in `run`, a copy of its splits read back from disk,
+/// unless the file already has it from being saved after the attempt finished.
+pub fn merge_attempt(run: &mut Run, timer: &Timer) {
+    if timer.current_phase() == TimerPhase::NotRunning {
+        return;
+    }
+    let splits: Vec<_> = timer
+        .run()
+        .segments()
+        .iter()
+        .map(|segment| segment.split_time())
+        .collect();
+    // resetting a copy fills in the attempt's time, dates and pause time
+    let finished = timer.clone().into_run(true);
+    let Some(attempt) = finished.attempt_history().last() else {
+        return;
+    };
+    // splits files keep dates to the second
+    let second = |attempt: &Attempt| attempt.started().map(|date| date.time.unix_timestamp());
+    if run
+        .attempt_history()
+        .last()
+        .is_some_and(|saved| second(saved).is_some() && second(saved) == second(attempt))
+    {
+        return;
+    }
+    let reached = timer.current_split_index().unwrap_or(0);
+    record_attempt(
+        run,
+        &splits,
+        attempt,
+        timer.current_timing_method(),
+        reached,
+    );
+}
+
+/// Records an attempt with its split times in both timing methods, like the
+/// timer's own on reset. `attempt` brings the attempt's time, dates and pause
+/// time, `reached` is how many segments it got through, and `method` decides
+/// whether it's a new PB.
+pub fn record_attempt(
+    run: &mut Run,
+    splits: &[Time],
+    attempt: &Attempt,
+    method: TimingMethod,
+    reached: usize,
+) {
+    for (segment, split) in run.segments_mut().iter_mut().zip(splits) {
+        segment.clear_split_info();
+        segment.set_split_time(*split);
+    }
+
+    run.start_next_run();
+    run.add_attempt(
+        attempt.time(),
+        attempt.started(),
+        attempt.ended(),
+        attempt.pause_time(),
+    );
+
+    for method in [TimingMethod::RealTime, TimingMethod::GameTime] {
+        let mut previous_split = TimeSpan::zero();
+        for segment in run.segments_mut() {
+            let Some(split) = segment.split_time()[method] else {
+                continue;
+            };
+            let segment_time = split - previous_split;
+            previous_split = split;
+            if segment.best_segment_time()[method].is_none_or(|best| segment_time < best) {
+                segment.best_segment_time_mut()[method] = Some(segment_time);
+            }
+        }
+    }
+
+    let last = run.segments().last().unwrap();
+    let (final_time, pb) = (
+        last.split_time()[method],
+        last.personal_best_split_time()[method],
+    );
+    if final_time.is_some_and(|time| pb.is_none_or(|pb| time < pb)) {
+        run.import_pb_into_segment_history();
+        run.fix_splits();
+        for segment in run.segments_mut() {
+            let split_time = segment.split_time();
+            segment.set_personal_best_split_time(split_time);
+        }
+        run.clear_run_id();
+    }
+
+    run.update_segment_history(reached);
+    for segment in run.segments_mut() {
+        segment.clear_split_info();
+    }
+    run.fix_splits();
+    run.regenerate_comparisons();
+}
+
+#[cfg(test)]
+mod tests {
+    use livesplit::{run::parser, run::saver, Segment};
+
+    use super::*;
+
+    /// A timer that ran a whole attempt, and its run from before the attempt.
+    fn finished_timer() -> (Timer, Run) {
+        let mut run = Run::new();
+        run.push_segment(Segment::new("one"));
+        run.push_segment(Segment::new("two"));
+        let mut timer = Timer::new(run.clone()).unwrap();
+        timer.start();
+        timer.split();
+        timer.split();
+        (timer, run)
+    }
+
+    fn reload(run: &Run) -> Run {
+        let mut lss = String::new();
+        saver::livesplit::save_run(run, &mut lss).unwrap();
+        parser::livesplit::parse(&lss).unwrap()
+    }
+
+    #[test]
+    fn merges_an_unsaved_attempt() {
+        let (timer, before) = finished_timer();
+        let mut run = reload(&before);
+        merge_attempt(&mut run, &timer);
+        assert_eq!(run.attempt_history().len(), 1);
+        assert_eq!(run.attempt_count(), 1);
+    }
+
+    #[test]
+    fn skips_an_attempt_saved_before_reloading() {
+        let (timer, _) = finished_timer();
+        let mut run = reload(&timer.clone().into_run(true));
+        merge_attempt(&mut run, &timer);
+        assert_eq!(run.attempt_history().len(), 1);
+        assert_eq!(run.attempt_count(), 1);
+    }
+}
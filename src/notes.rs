@@ -0,0 +1,31 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sidecar::Sidecar;
+
+/// Free-form notes attached to attempts, keyed by attempt history index.
+/// Lives in a sidecar next to the splits file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AttemptNotes(BTreeMap<String, String>);
+
+impl Sidecar for AttemptNotes {
+    const EXTENSION: &'static str = "notes.toml";
+}
+
+impl AttemptNotes {
+    pub fn get(&self, attempt: i32) -> Option<&str> {
+        self.0.get(&attempt.to_string()).map(String::as_str)
+    }
+
+    /// Sets the note for an attempt, removing it if the note is blank.
+    pub fn set(&mut self, attempt: i32, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            self.0.remove(&attempt.to_string());
+        } else {
+            self.0.insert(attempt.to_string(), note.to_string());
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Racing someone else's splits by bringing one of their comparisons into the run.
+
+use livesplit::{comparison::personal_best, Run, Time};
+
+/// Copies `comparison` from `other` into `run` as the comparison `name`, replacing an
+/// earlier import. Segments are matched up by name in order, so a route that adds or
+/// drops segments still lines up where it agrees. Returns the names of the run's
+/// segments that had no match and were left without a time.
+pub fn import_comparison(
+    run: &mut Run,
+    other: &Run,
+    comparison: &str,
+    name: &str,
+) -> Result<Vec<String>, String> {
+    if !other.comparisons().any(|existing| existing == comparison) {
+        return Err(format!(
+            "there's no comparison called {comparison:?} in that file"
+        ));
+    }
+    if name == personal_best::NAME {
+        return Err(String::from("that would overwrite the personal best"));
+    }
+    if !run.comparisons().any(|existing| existing == name) {
+        run.add_custom_comparison(name)
+            .map_err(|_| format!("can't add a comparison called {name:?}"))?;
+    } else if !run
+        .custom_comparisons()
+        .iter()
+        .any(|existing| existing == name)
+    {
+        return Err(format!(
+            "{name:?} is one of the comparisons shplit works out itself"
+        ));
+    }
+    let mut next = 0;
+    let mut unmatched = Vec::new();
+    for segment in run.segments_mut() {
+        let found = other.segments()[next..]
+            .iter()
+            .position(|theirs| theirs.name() == segment.name());
+        *segment.comparison_mut(name) = match found {
+            Some(offset) => {
+                next += offset + 1;
+                other.segment(next - 1).comparison(comparison)
+            }
+            None => {
+                unmatched.push(segment.name().to_string());
+                Time::default()
+            }
+        };
+    }
+    Ok(unmatched)
+}
@@ -0,0 +1,172 @@
+//! A server speaking LiveSplit's plain text protocol over TCP: one command per
+//! line, with a reply line for the commands that ask for something.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+use livesplit::{analysis, SharedTimer, TimeSpan, Timer, TimerPhase, TimingMethod};
+
+use crate::timefmt::{format_delta, format_short, parse_time, Rounding};
+
+/// Starts accepting connections on a background thread, each client on its own thread.
+pub fn spawn(timer: SharedTimer, address: &str) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let timer = timer.clone();
+            thread::spawn(move || serve(stream, timer));
+        }
+    });
+    Ok(())
+}
+
+fn serve(stream: TcpStream, timer: SharedTimer) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else { break };
+        let reply = handle(&mut timer.write().unwrap(), line.trim());
+        if let Some(reply) = reply {
+            if writeln!(writer, "{reply}").is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Runs a single command, returning the reply if it has one. Unknown commands are ignored.
+pub fn handle(timer: &mut Timer, line: &str) -> Option<String> {
+    let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+    let argument = argument.trim();
+    let method = timer.current_timing_method();
+    let current_split = timer.current_split_index();
+    match command {
+        "starttimer" => timer.start(),
+        "startorsplit" => timer.split_or_start(),
+        "split" => timer.split(),
+        "unsplit" => timer.undo_split(),
+        "skipsplit" => timer.skip_split(),
+        "reset" => timer.reset(true),
+        "pause" => timer.pause(),
+        "resume" => timer.resume(),
+        "togglepause" => timer.toggle_pause(),
+        "initgametime" => timer.initialize_game_time(),
+        "pausegametime" => timer.pause_game_time(),
+        "unpausegametime" => timer.resume_game_time(),
+        "setgametime" => {
+            if let Some(time) = parse_time(argument) {
+                timer.set_game_time(time);
+            }
+        }
+        "setloadingtimes" => {
+            if let Some(time) = parse_time(argument) {
+                timer.set_loading_times(time);
+            }
+        }
+        "setcomparison" => {
+            timer.set_current_comparison(argument).ok();
+        }
+        "switchto" => match argument {
+            "realtime" => timer.set_current_timing_method(TimingMethod::RealTime),
+            "gametime" => timer.set_current_timing_method(TimingMethod::GameTime),
+            _ => {}
+        },
+        "getdelta" => {
+            let comparison = match argument {
+                "" => timer.current_comparison(),
+                comparison => comparison,
+            };
+            let delta = current_split.and_then(|i| {
+                let i = i.min(timer.run().len() - 1);
+                analysis::last_delta(timer.run(), i, comparison, method)
+            });
+            return Some(delta.map_or(String::from("-"), |delta| {
+                format_delta(delta, Rounding::Truncate)
+            }));
+        }
+        "getcurrenttime" => return Some(time(timer.snapshot().current_time()[method])),
+        "getcurrentrealtime" => return Some(time(timer.snapshot().current_time().real_time)),
+        "getcurrentgametime" => return Some(time(timer.snapshot().current_time().game_time)),
+        "getsplitindex" => {
+            return Some(current_split.map_or(String::from("-1"), |i| i.to_string()))
+        }
+        "getcurrentsplitname" => {
+            return Some(
+                timer
+                    .current_split()
+                    .map_or("-", |split| split.name())
+                    .to_string(),
+            )
+        }
+        "getprevioussplitname" => {
+            let previous = current_split
+                .and_then(|i| i.checked_sub(1))
+                .map(|i| timer.run().segment(i).name());
+            return Some(previous.unwrap_or("-").to_string());
+        }
+        "getlastsplittime" => {
+            let previous = current_split
+                .and_then(|i| i.checked_sub(1))
+                .and_then(|i| timer.run().segment(i).split_time()[method]);
+            return Some(time(previous));
+        }
+        "getcomparisonsplittime" => {
+            let comparison = timer
+                .current_split()
+                .and_then(|split| split.comparison(timer.current_comparison())[method]);
+            return Some(time(comparison));
+        }
+        "getfinaltime" => {
+            let comparison = match argument {
+                "" => timer.current_comparison(),
+                comparison => comparison,
+            };
+            let last = timer.run().segments().last().unwrap();
+            let final_time = match timer.current_phase() {
+                TimerPhase::Ended => last.split_time()[method],
+                _ => last.comparison(comparison)[method],
+            };
+            return Some(time(final_time));
+        }
+        "getpredictedtime" | "getbestpossibletime" => {
+            let comparison = match (command, argument) {
+                ("getbestpossibletime", _) => livesplit::comparison::best_segments::NAME,
+                (_, "") => timer.current_comparison(),
+                (_, comparison) => comparison,
+            };
+            let pace = analysis::current_pace::calculate(&timer.snapshot(), comparison).0;
+            return Some(time(pace));
+        }
+        "getattemptcount" => return Some(timer.run().attempt_count().to_string()),
+        "getcompletedcount" => {
+            let completed = timer
+                .run()
+                .attempt_history()
+                .iter()
+                .filter(|attempt| attempt.time().real_time.is_some())
+                .count();
+            return Some(completed.to_string());
+        }
+        "getcurrenttimerphase" => {
+            return Some(String::from(match timer.current_phase() {
+                TimerPhase::NotRunning => "NotRunning",
+                TimerPhase::Running => "Running",
+                TimerPhase::Ended => "Ended",
+                TimerPhase::Paused => "Paused",
+            }))
+        }
+        "ping" => return Some(String::from("pong")),
+        _ => {}
+    }
+    None
+}
+
+fn time(time: Option<TimeSpan>) -> String {
+    time.map_or(String::from("-"), |time| {
+        format_short(time, Rounding::Truncate)
+    })
+}
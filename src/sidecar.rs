@@ -0,0 +1,34 @@
+//! Files kept next to a splits file for what the `.lss` has no place for,
+//! so the splits file itself is left untouched.
+
+use std::{
+    error::Error,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+pub trait Sidecar: Default + Serialize + for<'a> Deserialize<'a> {
+    /// Takes the place of the splits file's extension, e.g. `notes.toml`.
+    const EXTENSION: &'static str;
+
+    fn path_for(split_file: &Path) -> PathBuf {
+        split_file.with_extension(Self::EXTENSION)
+    }
+
+    /// Loads the sidecar of `split_file`, empty if it doesn't have one yet.
+    fn load(split_file: &Path) -> Result<Self, Box<dyn Error>> {
+        let path = Self::path_for(split_file);
+        if !path.try_exists()? {
+            return Ok(Self::default());
+        }
+        Ok(toml::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    fn save(&self, split_file: &Path) -> Result<(), Box<dyn Error>> {
+        std::fs::File::create(Self::path_for(split_file))?
+            .write_all(toml::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+//! Saving what's on screen as an SVG image, for sharing a run without reaching
+//! for a screenshot tool. Every cell keeps its colors and modifiers, each run of
+//! the same style is stretched to the width of its cells so the grid lines up in
+//! any monospace font.
+
+use std::{error::Error, fmt::Write as _, path::Path};
+
+use tui::{
+    buffer::{Buffer, Cell},
+    style::{Color, Modifier},
+};
+
+use crate::theme::to_rgb;
+
+const CELL_WIDTH: u16 = 10;
+const CELL_HEIGHT: u16 = 20;
+const FONT_SIZE: u16 = 16;
+/// What the terminal's own default colors are drawn as.
+const DEFAULT_FG: [u8; 3] = [229, 229, 229];
+const DEFAULT_BG: [u8; 3] = [0, 0, 0];
+
+pub fn save(buffer: &Buffer, path: &Path) -> Result<(), Box<dyn Error>> {
+    std::fs::write(path, render(buffer))?;
+    Ok(())
+}
+
+fn render(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let (width, height) = (area.width * CELL_WIDTH, area.height * CELL_HEIGHT);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         font-family=\"monospace\" font-size=\"{FONT_SIZE}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        hex(DEFAULT_BG)
+    );
+    for y in 0..area.height {
+        let mut x = 0;
+        while x < area.width {
+            let cell = buffer.get(area.x + x, area.y + y);
+            let mut text = String::new();
+            let start = x;
+            while x < area.width {
+                let next = buffer.get(area.x + x, area.y + y);
+                if !same_style(cell, next) {
+                    break;
+                }
+                text.push_str(&next.symbol);
+                x += 1;
+            }
+            write_span(&mut svg, cell, start, y, x - start, &text);
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn same_style(a: &Cell, b: &Cell) -> bool {
+    a.fg == b.fg && a.bg == b.bg && a.modifier == b.modifier
+}
+
+/// Draws `len` cells starting at column `x`, all in `cell`'s style.
+fn write_span(svg: &mut String, cell: &Cell, x: u16, y: u16, len: u16, text: &str) {
+    let reversed = cell.modifier.contains(Modifier::REVERSED);
+    let (mut fg, mut bg) = (color(cell.fg, DEFAULT_FG), color(cell.bg, DEFAULT_BG));
+    if reversed {
+        (fg, bg) = (bg, fg);
+    }
+    let (left, top, width) = (x * CELL_WIDTH, y * CELL_HEIGHT, len * CELL_WIDTH);
+    // the default background is already drawn behind everything
+    if cell.bg != Color::Reset || reversed {
+        writeln!(
+            svg,
+            "<rect x=\"{left}\" y=\"{top}\" width=\"{width}\" height=\"{CELL_HEIGHT}\" fill=\"{bg}\"/>"
+        )
+        .unwrap();
+    }
+    if text.trim().is_empty() || cell.modifier.contains(Modifier::HIDDEN) {
+        return;
+    }
+    let mut attributes = String::new();
+    if cell.modifier.contains(Modifier::BOLD) {
+        attributes.push_str(" font-weight=\"bold\"");
+    }
+    if cell.modifier.contains(Modifier::ITALIC) {
+        attributes.push_str(" font-style=\"italic\"");
+    }
+    if cell.modifier.contains(Modifier::DIM) {
+        attributes.push_str(" opacity=\"0.6\"");
+    }
+    match (
+        cell.modifier.contains(Modifier::UNDERLINED),
+        cell.modifier.contains(Modifier::CROSSED_OUT),
+    ) {
+        (true, true) => attributes.push_str(" text-decoration=\"underline line-through\""),
+        (true, false) => attributes.push_str(" text-decoration=\"underline\""),
+        (false, true) => attributes.push_str(" text-decoration=\"line-through\""),
+        (false, false) => {}
+    }
+    // the baseline sits a little above the bottom of the cell, like in a terminal
+    let baseline = top + CELL_HEIGHT - CELL_HEIGHT / 4;
+    writeln!(
+        svg,
+        "<text x=\"{left}\" y=\"{baseline}\" textLength=\"{width}\" lengthAdjust=\"spacingAndGlyphs\" \
+         xml:space=\"preserve\" fill=\"{fg}\"{attributes}>{}</text>",
+        escape(text)
+    )
+    .unwrap();
+}
+
+fn color(color: Color, default: [u8; 3]) -> String {
+    hex(to_rgb(color).unwrap_or(default))
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
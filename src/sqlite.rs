@@ -0,0 +1,93 @@
+//! Writing the attempt history to a SQLite database for querying it elsewhere.
+
+use std::{error::Error, path::Path};
+
+use livesplit::{AtomicDateTime, Run, TimeSpan};
+use rusqlite::{params, Connection};
+
+const SCHEMA: &str = "
+DROP TABLE IF EXISTS attempts;
+DROP TABLE IF EXISTS segments;
+DROP TABLE IF EXISTS segment_times;
+CREATE TABLE attempts (
+    id INTEGER PRIMARY KEY,
+    started TEXT,
+    ended TEXT,
+    real_time REAL,
+    game_time REAL,
+    pause_time REAL
+);
+CREATE TABLE segments (
+    idx INTEGER PRIMARY KEY,
+    name TEXT NOT NULL
+);
+CREATE TABLE segment_times (
+    attempt INTEGER NOT NULL,
+    segment INTEGER NOT NULL,
+    real_time REAL,
+    game_time REAL,
+    PRIMARY KEY (attempt, segment)
+);
+";
+
+/// Replaces the tables in `db` with the run's attempts, their final times, and
+/// the time of every segment in every attempt. Times are in seconds and dates
+/// in UTC, in the format SQLite's date functions take.
+pub fn export(run: &Run, db: &Path) -> Result<(), Box<dyn Error>> {
+    let mut connection = Connection::open(db)?;
+    // all or nothing, so a failed export doesn't leave half the tables behind
+    let transaction = connection.transaction()?;
+    transaction.execute_batch(SCHEMA)?;
+    {
+        let mut insert =
+            transaction.prepare("INSERT INTO attempts VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
+        for attempt in run.attempt_history() {
+            let time = attempt.time();
+            insert.execute(params![
+                attempt.index(),
+                date(attempt.started()),
+                date(attempt.ended()),
+                seconds(time.real_time),
+                seconds(time.game_time),
+                seconds(attempt.pause_time()),
+            ])?;
+        }
+        let mut insert_segment = transaction.prepare("INSERT INTO segments VALUES (?1, ?2)")?;
+        let mut insert_time =
+            transaction.prepare("INSERT INTO segment_times VALUES (?1, ?2, ?3, ?4)")?;
+        for (i, segment) in run.segments().iter().enumerate() {
+            insert_segment.execute(params![i, segment.name()])?;
+            for &(attempt, time) in segment.segment_history().iter() {
+                // negative ids are times from before the history was kept
+                if attempt < 1 {
+                    continue;
+                }
+                insert_time.execute(params![
+                    attempt,
+                    i,
+                    seconds(time.real_time),
+                    seconds(time.game_time),
+                ])?;
+            }
+        }
+    }
+    transaction.commit()?;
+    Ok(())
+}
+
+fn seconds(time: Option<TimeSpan>) -> Option<f64> {
+    time.map(|time| time.total_seconds())
+}
+
+fn date(date: Option<AtomicDateTime>) -> Option<String> {
+    let time = date?.time;
+    Some(format!(
+        "{}-{:02}-{:02} {:02}:{:02}:{:02}",
+        time.year(),
+        u8::from(time.month()),
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second()
+    ))
+}
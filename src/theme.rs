@@ -0,0 +1,233 @@
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tui::style::{Color, Modifier, Style};
+
+/// A style as written in the config, e.g. `{ fg = "black", bg = "#ffcc00", modifiers = ["bold"] }`.
+/// Colors and modifiers that don't parse are ignored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StyleConfig {
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub modifiers: Vec<String>,
+}
+
+impl StyleConfig {
+    pub fn to_style(&self, depth: ColorDepth) -> Style {
+        let mut style = Style::default();
+        if let Some(fg) = self.fg.as_deref().and_then(|fg| fg.parse::<Color>().ok()) {
+            style = style.fg(depth.downsample(fg));
+        }
+        if let Some(bg) = self.bg.as_deref().and_then(|bg| bg.parse::<Color>().ok()) {
+            style = style.bg(depth.downsample(bg));
+        }
+        for modifier in self.modifiers.iter().filter_map(|m| parse_modifier(m)) {
+            style = style.add_modifier(modifier);
+        }
+        style
+    }
+}
+
+/// How many colors the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorDepth {
+    #[serde(alias = "24bit")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Ansi16,
+}
+
+/// The 16 ANSI colors as xterm draws them, in palette order.
+const ANSI_16: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::Red, [205, 0, 0]),
+    (Color::Green, [0, 205, 0]),
+    (Color::Yellow, [205, 205, 0]),
+    (Color::Blue, [0, 0, 238]),
+    (Color::Magenta, [205, 0, 205]),
+    (Color::Cyan, [0, 205, 205]),
+    (Color::Gray, [229, 229, 229]),
+    (Color::DarkGray, [127, 127, 127]),
+    (Color::LightRed, [255, 0, 0]),
+    (Color::LightGreen, [0, 255, 0]),
+    (Color::LightYellow, [255, 255, 0]),
+    (Color::LightBlue, [92, 92, 255]),
+    (Color::LightMagenta, [255, 0, 255]),
+    (Color::LightCyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+/// Channel values of the 6x6x6 cube in the 256 color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    /// Guesses from `COLORTERM` and `TERM`, assuming only 16 colors when neither says more.
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        let term = std::env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// Maps a color to the closest one the terminal can show. Named colors are left alone.
+    pub fn downsample(self, color: Color) -> Color {
+        let rgb = match color {
+            Color::Rgb(r, g, b) => [r, g, b],
+            Color::Indexed(index) if self == Self::Ansi16 => indexed_rgb(index),
+            _ => return color,
+        };
+        match self {
+            Self::TrueColor => color,
+            Self::Ansi256 => Color::Indexed(nearest_indexed(rgb)),
+            Self::Ansi16 => nearest_ansi(rgb),
+        }
+    }
+}
+
+/// The RGB value a color is drawn with, going by xterm's palette for the named and
+/// indexed ones. `None` for the terminal's own default.
+#[cfg(feature = "snapshot")]
+pub fn to_rgb(color: Color) -> Option<[u8; 3]> {
+    let named = ANSI_16.iter().find(|(named, _)| *named == color);
+    match color {
+        Color::Reset => None,
+        Color::Rgb(r, g, b) => Some([r, g, b]),
+        Color::Indexed(index) => Some(indexed_rgb(index)),
+        _ => named.map(|(_, rgb)| *rgb),
+    }
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b)
+        .map(|(&a, b)| (a as i32 - b as i32).pow(2) as u32)
+        .sum()
+}
+
+fn nearest_ansi(rgb: [u8; 3]) -> Color {
+    ANSI_16
+        .iter()
+        .min_by_key(|(_, ansi)| distance(rgb, *ansi))
+        .map(|(color, _)| *color)
+        .unwrap()
+}
+
+/// The closest color out of the cube and the grayscale ramp, leaving out the
+/// first 16 since terminals are free to draw those however they like.
+fn nearest_indexed(rgb: [u8; 3]) -> u8 {
+    let level = |channel: u8| {
+        (0..6)
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - channel as i32).abs())
+            .unwrap()
+    };
+    let [r, g, b] = rgb.map(level);
+    let cube = 16 + 36 * r as u8 + 6 * g as u8 + b as u8;
+    let average = rgb.iter().map(|&channel| channel as u32).sum::<u32>() / 3;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+    match distance(rgb, indexed_rgb(gray)) < distance(rgb, indexed_rgb(cube)) {
+        true => gray,
+        false => cube,
+    }
+}
+
+fn indexed_rgb(index: u8) -> [u8; 3] {
+    match index {
+        0..=15 => ANSI_16[index as usize].1,
+        16..=231 => {
+            let index = index - 16;
+            [index / 36, index / 6 % 6, index % 6].map(|level| CUBE_LEVELS[level as usize])
+        }
+        _ => [8 + (index - 232) * 10; 3],
+    }
+}
+
+fn parse_modifier(modifier: &str) -> Option<Modifier> {
+    Some(match modifier.to_lowercase().as_str() {
+        "bold" => Modifier::BOLD,
+        "dim" => Modifier::DIM,
+        "italic" => Modifier::ITALIC,
+        "underlined" | "underline" => Modifier::UNDERLINED,
+        "slow_blink" | "blink" => Modifier::SLOW_BLINK,
+        "rapid_blink" => Modifier::RAPID_BLINK,
+        "reversed" | "reverse" => Modifier::REVERSED,
+        "hidden" => Modifier::HIDDEN,
+        "crossed_out" | "strikethrough" => Modifier::CROSSED_OUT,
+        _ => return None,
+    })
+}
+
+/// The footer clock, styled apart from the table since it's what gets looked at most.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockTheme {
+    #[serde(flatten)]
+    pub style: StyleConfig,
+    /// Draw the clock in big digits above the footer instead of in it.
+    pub big: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    /// The row of the current split.
+    pub highlight: StyleConfig,
+    /// The current split's name while a gold is still possible.
+    pub gold: StyleConfig,
+    /// Deltas that come out even with the comparison at the shown precision.
+    pub tie: StyleConfig,
+    pub clock: ClockTheme,
+    /// One color for borders and labels, for a consistent look from a single setting.
+    pub accent: Option<String>,
+    /// Colors the terminal supports, detected from the environment if unset.
+    pub color_depth: Option<ColorDepth>,
+}
+
+/// The environment doesn't change while shplit runs, so it's only looked at once.
+static DETECTED_DEPTH: OnceLock<ColorDepth> = OnceLock::new();
+
+impl Theme {
+    pub fn color_depth(&self) -> ColorDepth {
+        self.color_depth
+            .unwrap_or_else(|| *DETECTED_DEPTH.get_or_init(ColorDepth::detect))
+    }
+
+    /// The accent color as a style, empty if unset or it doesn't parse.
+    pub fn accent(&self) -> Style {
+        StyleConfig {
+            fg: self.accent.clone(),
+            ..Default::default()
+        }
+        .to_style(self.color_depth())
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            highlight: StyleConfig {
+                modifiers: vec![String::from("reversed")],
+                ..Default::default()
+            },
+            gold: StyleConfig {
+                fg: Some(String::from("yellow")),
+                ..Default::default()
+            },
+            tie: StyleConfig {
+                fg: Some(String::from("gray")),
+                ..Default::default()
+            },
+            clock: ClockTheme::default(),
+            accent: None,
+            color_depth: None,
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use livesplit::TimeSpan;
+use serde::{Deserialize, Serialize};
+
+/// How times are brought down to the displayed precision.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    /// Drop the digits that aren't shown, like LiveSplit does by default.
+    #[default]
+    Truncate,
+    Round,
+}
+
+/// The absolute value of a time in units of `unit_nanos`.
+fn whole_units(time: TimeSpan, unit_nanos: i128, rounding: Rounding) -> i128 {
+    let nanos = time.to_duration().whole_nanoseconds().abs();
+    match rounding {
+        Rounding::Truncate => nanos / unit_nanos,
+        Rounding::Round => (nanos + unit_nanos / 2) / unit_nanos,
+    }
+}
+
+/// Whether a delta shows as zero once formatted, which counts as neither ahead nor behind.
+pub fn is_tie(delta: TimeSpan, rounding: Rounding) -> bool {
+    whole_units(delta, 10_000_000, rounding) == 0
+}
+
+/// Formats a time as hh:mm:ss.xxx.
+pub fn format_time(time: TimeSpan, rounding: Rounding) -> String {
+    let sign = if time < TimeSpan::zero() { "-" } else { "" };
+    let millis = whole_units(time, 1_000_000, rounding);
+    format!(
+        "{sign}{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// Formats a time as hh:mm:ss. Always truncates, so a running clock never shows a second early.
+pub fn format_whole_seconds(time: TimeSpan) -> String {
+    let seconds = whole_units(time, 1_000_000_000, Rounding::Truncate);
+    let sign = if time < TimeSpan::zero() { "-" } else { "" };
+    format!(
+        "{sign}{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Formats a difference in time as +m:ss.xx, leaving out the minutes when there are none.
+pub fn format_delta(delta: TimeSpan, rounding: Rounding) -> String {
+    let sign = if delta < TimeSpan::zero() { '-' } else { '+' };
+    let centis = whole_units(delta, 10_000_000, rounding);
+    let (minutes, seconds, centis) = (centis / 6000, centis / 100 % 60, centis % 100);
+    if minutes > 0 {
+        format!("{sign}{minutes}:{seconds:02}.{centis:02}")
+    } else {
+        format!("{sign}{seconds}.{centis:02}")
+    }
+}
+
+/// Formats a time as h:mm:ss.xx, leaving out the hours and minutes when there are none.
+pub fn format_short(time: TimeSpan, rounding: Rounding) -> String {
+    let sign = if time < TimeSpan::zero() { "-" } else { "" };
+    let centis = whole_units(time, 10_000_000, rounding);
+    let (hours, minutes) = (centis / 360_000, centis / 6000 % 60);
+    let (seconds, centis) = (centis / 100 % 60, centis % 100);
+    if hours > 0 {
+        format!("{sign}{hours}:{minutes:02}:{seconds:02}.{centis:02}")
+    } else if minutes > 0 {
+        format!("{sign}{minutes}:{seconds:02}.{centis:02}")
+    } else {
+        format!("{sign}{seconds}.{centis:02}")
+    }
+}
+
+/// Parses times like `1:23:45.6`, `83:45`, `45.6` or `1h 23m 45.6s`.
+pub fn parse_time(text: &str) -> Option<TimeSpan> {
+    let text = text.trim().replace(',', ".");
+    if text.is_empty() {
+        return None;
+    }
+    if !text.contains(['h', 'm', 's']) {
+        return text
+            .parse::<TimeSpan>()
+            .ok()
+            .filter(|time| *time >= TimeSpan::zero());
+    }
+    let mut seconds = 0.0;
+    let mut number = String::new();
+    for c in text.chars() {
+        match c {
+            '0'..='9' | '.' => number.push(c),
+            'h' | 'm' | 's' => {
+                let unit = match c {
+                    'h' => 3600.0,
+                    'm' => 60.0,
+                    _ => 1.0,
+                };
+                seconds += number.parse::<f64>().ok()? * unit;
+                number.clear();
+            }
+            c if c.is_whitespace() => {}
+            _ => return None,
+        }
+    }
+    // a trailing number without a unit is ambiguous
+    number.is_empty().then(|| TimeSpan::from_seconds(seconds))
+}
+
+/// Parses a time that may be negative, like `+2`, `-1.5` or `-1m 3s`.
+pub fn parse_signed_time(text: &str) -> Option<TimeSpan> {
+    let text = text.trim();
+    match text.strip_prefix('-') {
+        Some(rest) => parse_time(rest).map(|time| TimeSpan::zero() - time),
+        None => parse_time(text.strip_prefix('+').unwrap_or(text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: f64) -> TimeSpan {
+        TimeSpan::from_milliseconds(millis)
+    }
+
+    #[test]
+    fn truncates() {
+        assert_eq!(format_delta(millis(3195.0), Rounding::Truncate), "+3.19");
+        assert_eq!(format_delta(millis(-3199.0), Rounding::Truncate), "-3.19");
+        assert_eq!(
+            format_time(millis(59_999.6), Rounding::Truncate),
+            "00:00:59.999"
+        );
+    }
+
+    #[test]
+    fn rounds() {
+        assert_eq!(format_delta(millis(3195.0), Rounding::Round), "+3.20");
+        assert_eq!(format_delta(millis(-3194.0), Rounding::Round), "-3.19");
+        assert_eq!(format_delta(millis(59_996.0), Rounding::Round), "+1:00.00");
+        assert_eq!(
+            format_time(millis(3_599_999.6), Rounding::Round),
+            "01:00:00.000"
+        );
+    }
+
+    #[test]
+    fn parses_times() {
+        assert_eq!(parse_time("42"), Some(millis(42_000.0)));
+        assert_eq!(parse_time("1:05"), Some(millis(65_000.0)));
+        assert_eq!(parse_time("1:02:03"), Some(millis(3_723_000.0)));
+        assert_eq!(parse_time("1:05.25"), Some(millis(65_250.0)));
+        assert_eq!(parse_time("12,5"), Some(millis(12_500.0)));
+        assert_eq!(parse_time("1m 5.5s"), Some(millis(65_500.0)));
+        assert_eq!(parse_time(" 1h2m3s "), Some(millis(3_723_000.0)));
+    }
+
+    #[test]
+    fn parses_signed_times() {
+        assert_eq!(parse_signed_time("+2"), Some(millis(2_000.0)));
+        assert_eq!(parse_signed_time("-1.5"), Some(millis(-1_500.0)));
+        assert_eq!(parse_signed_time("-1:05"), Some(millis(-65_000.0)));
+        assert_eq!(parse_signed_time("-1m 3s"), Some(millis(-63_000.0)));
+    }
+
+    #[test]
+    fn rejects_bad_times() {
+        for text in ["", "  ", "abc", "1m 5", "1x", "-5", "--5"] {
+            assert_eq!(parse_time(text), None, "{text:?}");
+        }
+        assert_eq!(parse_signed_time("--5"), None);
+        assert_eq!(parse_signed_time("+-5"), None);
+    }
+}
@@ -0,0 +1,1078 @@
+use std::time::Duration;
+
+use livesplit::{Run, Time, TimeSpan, Timer, TimingMethod};
+use tui::{prelude::*, widgets::*};
+
+use crate::{
+    bigtext,
+    config::{Column, Config, CurrentRow, DeltaScope, Marquee, Section},
+    daily::Day,
+    keybinds,
+    library::Library,
+    notes::AttemptNotes,
+    timefmt::*,
+    App, Prompt, PromptKind, NOT_PERSISTENT, SAVED_DURATION,
+};
+
+/// How long a status message stays on screen.
+pub const STATUS_DURATION: Duration = Duration::from_secs(5);
+
+pub fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let saved = app
+        .last_saved
+        .is_some_and(|since| since.elapsed() < SAVED_DURATION);
+    let status = app
+        .current_status()
+        .map(str::to_string)
+        .or(saved.then(|| String::from("saved")));
+
+    let (min_width, min_height) = (app.config.min_width, app.config.min_height);
+    let size = f.size();
+    if size.width < min_width || size.height < min_height {
+        f.render_widget(
+            Paragraph::new(format!(
+                "terminal too small, shplit needs {min_width}x{min_height} (this is {}x{})",
+                size.width, size.height
+            ))
+            .wrap(Wrap { trim: true }),
+            size,
+        );
+        return;
+    }
+
+    if let Some(ref mut library) = app.library {
+        render_library(f, f.size(), &app.config, library);
+        return;
+    }
+
+    let Some(ref timer) = app.timer else {
+        // the splash stays up for a while, so keep saying why nothing sticks
+        let status = status.or((!app.config_writable).then(|| NOT_PERSISTENT.to_string()));
+        render_splash(f, f.size(), &app.config, status.as_deref());
+        return;
+    };
+
+    if app.big_timer {
+        render_big_timer(f, f.size(), timer, &app.config);
+        return;
+    }
+
+    let strip_height = !app.config.reference_deltas.is_empty() as u16;
+    let clock_height = match app.config.theme.clock.big {
+        true => bigtext::size("0", 1).1 as u16,
+        false => 0,
+    };
+    let rects = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(100),
+                Constraint::Length(strip_height),
+                Constraint::Length(clock_height),
+                Constraint::Min(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let panels = app.show_history as u32 + app.show_info as u32;
+    let (table_rect, side_rects) = if panels > 0 {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+            .split(rects[0]);
+        let side = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Ratio(1, panels); panels as usize])
+            .split(columns[1]);
+        (columns[0], side)
+    } else {
+        (rects[0], Default::default())
+    };
+
+    let just_split = app
+        .last_split
+        .filter(|(_, since)| since.elapsed().as_secs_f64() < app.config.split_result_secs)
+        .map(|(i, _)| i);
+    if app.stopwatch {
+        render_laps(f, table_rect, &app.config, &app.laps);
+    } else {
+        render_table(
+            f,
+            table_rect,
+            timer,
+            app.table_method.unwrap_or(timer.current_timing_method()),
+            &app.config,
+            just_split,
+            app.launched.elapsed(),
+            app.table_scroll.map(|(offset, _)| offset),
+            &mut app.table_state,
+        );
+    }
+    let mut side_rects = side_rects.iter();
+    if app.show_history {
+        let rect = *side_rects.next().unwrap();
+        render_history(f, rect, timer, &app.config, &app.notes);
+    }
+    if app.show_info {
+        let rect = *side_rects.next().unwrap();
+        render_info(f, rect, timer, &app.config, &app.split_clock, &app.warnings);
+    }
+    if strip_height > 0 {
+        render_reference_deltas(f, rects[1], timer, &app.config);
+    }
+    if clock_height > 0 {
+        render_big_clock(f, rects[2], timer, &app.config);
+    }
+    match app.prompt {
+        Some(ref prompt) => render_prompt(f, rects[3], timer, prompt),
+        None => render_footer(
+            f,
+            rects[3],
+            timer,
+            &app.config,
+            app.target,
+            &app.daily.today(app.local_offset),
+            status.as_deref(),
+        ),
+    }
+}
+
+pub fn render_library<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    config: &Config,
+    library: &mut Library,
+) {
+    let block = bordered(config).title("Library (enter to open, esc to close)");
+    if library.files.is_empty() {
+        f.render_widget(
+            Paragraph::new("No splits files found. Set splits_dir or library_paths in the config.")
+                .block(block),
+            area,
+        );
+        return;
+    }
+    let items: Vec<ListItem> = library
+        .files
+        .iter()
+        .map(|path| ListItem::new(path.to_string_lossy().into_owned()))
+        .collect();
+    f.render_stateful_widget(
+        List::new(items)
+            .block(block)
+            .highlight_style(config.theme.highlight.to_style(config.theme.color_depth())),
+        area,
+        &mut library.state,
+    );
+}
+
+pub fn render_splash<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    config: &Config,
+    status: Option<&str>,
+) {
+    let block = bordered(config)
+        .title("shplit")
+        .title_alignment(Alignment::Center);
+    let hint = config.splash_hint.clone().unwrap_or_else(|| {
+        format!(
+            "Drag and drop a splits file onto the window, or press {}. Press {} for a plain stopwatch.",
+            keybinds::display(&config.keybindings.open),
+            keybinds::display(&config.keybindings.stopwatch)
+        )
+    });
+    let mut text = vec![Line::from(hint)];
+    if let Some(status) = status {
+        text.extend([Line::default(), Line::from(status.to_string())]);
+    }
+    f.render_widget(
+        Paragraph::new(text)
+            .block(block)
+            .alignment(Alignment::Center),
+        area,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn render_table<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    timer: &Timer,
+    method: TimingMethod,
+    config: &Config,
+    just_split: Option<usize>,
+    frame_time: Duration,
+    scroll: Option<usize>,
+    state: &mut TableState,
+) {
+    // point it out when the table isn't timed the same way as the clock
+    let time_header = match (method == timer.current_timing_method(), method) {
+        (true, _) => "Time",
+        (false, TimingMethod::RealTime) => "Time (real)",
+        (false, TimingMethod::GameTime) => "Time (game)",
+    };
+    let header = Row::new(
+        std::iter::once("Segment").chain(config.table_columns().map(|column| match column {
+            Column::Time => time_header,
+            column => column.title(),
+        })),
+    )
+    .height(1)
+    .bottom_margin(1);
+
+    let current_split = timer.current_split_index();
+    let comparison = timer.current_comparison();
+    let offset = config.comparison_offset(comparison);
+    let live_time = timer.snapshot().current_time()[method];
+    let last_index = timer.run().len() - 1;
+    let snapshot = timer.snapshot();
+    let not_running = timer.current_phase() == livesplit::TimerPhase::NotRunning;
+    // still possible to gold the segment we're in
+    let gold_pace = current_split
+        .filter(|_| timer.current_phase().is_running() || timer.current_phase().is_paused())
+        .filter(|&i| {
+            let best = timer.run().segment(i).best_segment_time()[method];
+            let live = livesplit::analysis::live_segment_time(&snapshot, i, method);
+            matches!((live, best), (Some(live), Some(best)) if live < best)
+        });
+    let sections: Vec<&Section> = sections(config, timer.run().len()).collect();
+    // roughly what the name column gets after borders and column spacing
+    let name_width = (area.width.saturating_sub(2) as usize * 55 / 100).saturating_sub(1);
+    let mut rows = Vec::new();
+    let order: Vec<usize> = match config.reverse_order {
+        true => (0..=last_index).rev().collect(),
+        false => (0..=last_index).collect(),
+    };
+    for i in order {
+        let segment = timer.run().segment(i);
+        // a section's header stays on top of its segments either way round
+        let section = sections.iter().find(|section| match config.reverse_order {
+            true => section.end == i,
+            false => section.start == i,
+        });
+        if let Some(section) = section {
+            rows.push(section_row(timer, method, config, section, live_time));
+        }
+        let indent = match sections.iter().any(|section| section.contains(i)) {
+            true => "  ",
+            false => "",
+        };
+        let show_delta = match config.delta_scope {
+            DeltaScope::All => true,
+            DeltaScope::Final => i == last_index,
+        };
+        let delta = match (
+            segment.split_time()[method],
+            segment.comparison(comparison)[method],
+        ) {
+            (Some(split), Some(target)) if show_delta => Some(split - (target + offset)),
+            _ => None,
+        };
+        let delta_cell = match (delta, segment.comparison(comparison)[method]) {
+            (Some(delta), _) => {
+                Cell::from(format_delta(delta, config.rounding)).style(delta_style(delta, config))
+            }
+            (None, Some(target))
+                if config.delta_fallback
+                    && show_delta
+                    && current_split.is_none_or(|current| i >= current) =>
+            {
+                Cell::from(format_short(target + offset, config.rounding))
+                    .style(Style::default().fg(Color::DarkGray))
+            }
+            _ => Cell::from(""),
+        };
+        let split_time = match config.current_row {
+            _ if current_split != Some(i) => segment.split_time()[method],
+            CurrentRow::Empty | CurrentRow::Comparison => segment.split_time()[method],
+            CurrentRow::Total => live_time,
+            CurrentRow::Segment => livesplit::analysis::live_segment_time(&snapshot, i, method),
+        };
+        let preview = match segment.comparison(comparison)[method] {
+            Some(time) if config.preview_comparison && not_running => Some(time + offset),
+            Some(time)
+                if current_split == Some(i) && config.current_row == CurrentRow::Comparison =>
+            {
+                Some(time + offset)
+            }
+            _ => None,
+        };
+        let time_cell = match (preview, split_time) {
+            (Some(time), _) => Cell::from(format_short(time, config.rounding))
+                .style(Style::default().fg(Color::DarkGray)),
+            (None, time) => Cell::from(time.map_or(String::from("0:00"), |time| {
+                format_short(time, config.rounding)
+            })),
+        };
+        let name = match current_split == Some(i) {
+            true => marquee(
+                segment.name(),
+                name_width.saturating_sub(indent.len() + 2),
+                frame_time,
+                &config.marquee,
+            ),
+            false => segment.name().to_string(),
+        };
+        let name_cell = match gold_pace {
+            Some(gold) if gold == i => Cell::from(format!("{indent}{name} ★"))
+                .style(config.theme.gold.to_style(config.theme.color_depth())),
+            _ if just_split == Some(i) => split_result_cell(timer, method, config, i, indent),
+            _ => Cell::from(format!("{indent}{name}")),
+        };
+        let comparison_cell = Cell::from(
+            segment.comparison(comparison)[method].map_or(String::new(), |time| {
+                format_short(time + offset, config.rounding)
+            }),
+        );
+        let mut cells = vec![name_cell];
+        for column in config.table_columns() {
+            cells.push(match column {
+                Column::Delta => delta_cell.clone(),
+                Column::Time => time_cell.clone(),
+                Column::Comparison => comparison_cell.clone(),
+                Column::Average | Column::Median => {
+                    let statistic = column.statistic().unwrap();
+                    let time = livesplit::analysis::comparison_single_segment_time(
+                        timer.run(),
+                        i,
+                        statistic,
+                        method,
+                    );
+                    Cell::from(
+                        time.map_or(String::new(), |time| format_short(time, config.rounding)),
+                    )
+                }
+            });
+        }
+        rows.push(Row::new(cells));
+    }
+
+    // scrolled by hand: show the rows from there, highlighting the current one only if it's
+    // in view, since the table would otherwise scroll back to it
+    let mut scrolled_state;
+    let state = match scroll {
+        Some(offset) => {
+            // borders, the header and its margin
+            let chrome = if config.hide_header { 2 } else { 4 };
+            let visible = area.height.saturating_sub(chrome) as usize;
+            let offset = offset.min(rows.len().saturating_sub(visible));
+            rows.drain(..offset);
+            scrolled_state = TableState::default().with_selected(
+                state
+                    .selected()
+                    .filter(|&row| row >= offset && row < offset + visible)
+                    .map(|row| row - offset),
+            );
+            &mut scrolled_state
+        }
+        None => state,
+    };
+
+    let widths: Vec<Constraint> = std::iter::once(Constraint::Percentage(55))
+        .chain(config.table_columns().map(Column::width))
+        .collect();
+    let mut table = Table::new(rows)
+        .block(bordered(config))
+        .highlight_style(config.theme.highlight.to_style(config.theme.color_depth()))
+        .widths(&widths);
+    if !config.hide_header {
+        table = table.header(header);
+    }
+
+    f.render_stateful_widget(table, area, state);
+}
+
+/// The part of `text` to show in `width` columns, scrolling through it over time if it doesn't fit.
+fn marquee(text: &str, width: usize, frame_time: Duration, config: &Marquee) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= width || config.speed <= 0.0 {
+        return text.to_string();
+    }
+    let overflow = chars.len() - width;
+    // counted in characters scrolled, with a second's worth of hold at each end
+    let hold = match config.pause_at_ends {
+        true => config.speed.ceil() as usize,
+        false => 0,
+    };
+    let cycle = overflow + 2 * hold + 1;
+    let step = (frame_time.as_secs_f64() * config.speed) as usize % cycle;
+    let offset = step.saturating_sub(hold).min(overflow);
+    chars[offset..offset + width].iter().collect()
+}
+
+/// The name cell of a segment that was just split, with the segment's own time
+/// and whether it was a gold.
+fn split_result_cell<'a>(
+    timer: &'a Timer,
+    method: TimingMethod,
+    config: &Config,
+    i: usize,
+    indent: &str,
+) -> Cell<'a> {
+    let segments = timer.run().segments();
+    let name = segments[i].name();
+    let Some(split) = segments[i].split_time()[method] else {
+        return Cell::from(format!("{indent}{name}  skipped")).style(Style::default().bold());
+    };
+    let previous = segments[..i]
+        .iter()
+        .rev()
+        .find_map(|segment| segment.split_time()[method])
+        .unwrap_or_else(TimeSpan::zero);
+    let segment_time = split - previous;
+    let text = format!(
+        "{indent}{name}  {}",
+        format_time(segment_time, config.rounding)
+    );
+    match segments[i].best_segment_time()[method] {
+        Some(best) if segment_time >= best => Cell::from(text).style(Style::default().bold()),
+        _ => Cell::from(format!("{text} ★")).style(
+            config
+                .theme
+                .gold
+                .to_style(config.theme.color_depth())
+                .bold(),
+        ),
+    }
+}
+
+/// Stopwatch laps, newest last, with each lap's own time and the total.
+pub fn render_laps<B: Backend>(f: &mut Frame<B>, area: Rect, config: &Config, laps: &[TimeSpan]) {
+    let header = Row::new(["Lap", "Time", "Total"])
+        .height(1)
+        .bottom_margin(1);
+    let rows = laps.iter().enumerate().map(|(i, &total)| {
+        let previous = i.checked_sub(1).map_or_else(TimeSpan::zero, |i| laps[i]);
+        Row::new(vec![
+            Cell::from(format!("Lap {}", i + 1)),
+            Cell::from(format_short(total - previous, config.rounding)),
+            Cell::from(format_short(total, config.rounding)),
+        ])
+    });
+    let table = Table::new(rows)
+        .header(header)
+        .block(bordered(config))
+        .widths(&[
+            Constraint::Percentage(40),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+        ]);
+    f.render_widget(table, area);
+}
+
+pub fn render_history<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    timer: &Timer,
+    config: &Config,
+    notes: &AttemptNotes,
+) {
+    let method = timer.current_timing_method();
+    let items: Vec<ListItem> = timer
+        .run()
+        .attempt_history()
+        .iter()
+        .rev()
+        .map(|attempt| {
+            let time = attempt.time()[method]
+                .map_or(String::from("-"), |time| format_time(time, config.rounding));
+            let mut text = format!("#{} {time}", attempt.index());
+            if let Some(note) = notes.get(attempt.index()) {
+                text.push_str(" - ");
+                text.push_str(note);
+            }
+            ListItem::new(text)
+        })
+        .collect();
+    f.render_widget(
+        List::new(items).block(bordered(config).title("History")),
+        area,
+    );
+}
+
+pub fn render_info<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    timer: &Timer,
+    config: &Config,
+    split_clock: &[time::OffsetDateTime],
+    warnings: &[String],
+) {
+    let run = timer.run();
+    let method = timer.current_timing_method();
+    let sum_of_best =
+        livesplit::analysis::sum_of_segments::calculate_best(run.segments(), false, false, method);
+    let realistic = match config.unreliable_segments.is_empty() {
+        true => None,
+        false => Some(sum_of_best_time(run, method, config)),
+    };
+    let mut lines = vec![
+        Line::from(run.game_name().to_string()),
+        Line::from(run.category_name().to_string()),
+    ];
+    for (name, value) in variables(run) {
+        lines.push(Line::from(format!("{name}: {value}")));
+    }
+    lines.extend([
+        Line::from(format!("attempts: {}", run.attempt_count())),
+        Line::from(format!(
+            "sum of best: {}",
+            sum_of_best.map_or(String::from("-"), |time| format_time(time, config.rounding))
+        )),
+    ]);
+    if let Some(realistic) = realistic {
+        lines.push(Line::from(format!(
+            "without unreliable golds: {}",
+            realistic.map_or(String::from("-"), |time| format_time(time, config.rounding))
+        )));
+    }
+    if config.split_time_of_day && !split_clock.is_empty() {
+        lines.push(Line::default());
+        let names = std::iter::once("start").chain(run.segments().iter().map(|s| s.name()));
+        for (name, at) in names.zip(split_clock) {
+            lines.push(Line::from(format!(
+                "{:02}:{:02}:{:02}  {name}",
+                at.hour(),
+                at.minute(),
+                at.second()
+            )));
+        }
+    }
+    if !warnings.is_empty() {
+        lines.push(Line::default());
+    }
+    for warning in warnings {
+        lines.push(Line::styled(
+            format!("! {warning}"),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    f.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(bordered(config).title("Info")),
+        area,
+    );
+}
+
+/// What the splits file says about the category beyond its name: the platform and
+/// region, speedrun.com's variables like difficulty, and the runner's own variables.
+fn variables(run: &Run) -> Vec<(&str, &str)> {
+    let metadata = run.metadata();
+    let mut variables = Vec::new();
+    if !metadata.platform_name().is_empty() {
+        variables.push(("platform", metadata.platform_name()));
+    }
+    if !metadata.region_name().is_empty() {
+        variables.push(("region", metadata.region_name()));
+    }
+    if metadata.uses_emulator() {
+        variables.push(("emulator", "yes"));
+    }
+    variables.extend(
+        metadata
+            .speedrun_com_variables()
+            .map(|(name, value)| (name, value.as_str())),
+    );
+    variables.extend(
+        metadata
+            .custom_variables()
+            .filter(|(_, variable)| variable.is_permanent && !variable.value.is_empty())
+            .map(|(name, variable)| (name, variable.value.as_str())),
+    );
+    variables
+}
+
+pub fn render_prompt<B: Backend>(f: &mut Frame<B>, area: Rect, timer: &Timer, prompt: &Prompt) {
+    let label = match prompt.kind {
+        PromptKind::Note(attempt) => format!("note for #{attempt}"),
+        PromptKind::SplitTimes(ref times) => format!(
+            "split time for {} ({}/{}, empty to skip)",
+            timer.run().segment(times.len()).name(),
+            times.len() + 1,
+            timer.run().len()
+        ),
+        PromptKind::Target => String::from("target time (empty to clear)"),
+        PromptKind::Convert(ref kind) => {
+            format!("{kind} splits, save as .lss (esc to keep the original)")
+        }
+        PromptKind::Category(ref category) => {
+            format!("{category}, enter to time it or esc to pick another file")
+        }
+        PromptKind::Overwrite(ref path) => {
+            format!("{path} already exists, overwrite it? type yes to confirm")
+        }
+        PromptKind::ImportGolds => String::from("golds file to import"),
+        PromptKind::StartAt => String::from("start the clock from"),
+        PromptKind::RaceFile => String::from("splits file to race against"),
+        PromptKind::RaceComparison(ref other, _) => format!(
+            "comparison to import ({})",
+            other.comparisons().collect::<Vec<_>>().join(", ")
+        ),
+        PromptKind::Confirm(maintenance) => format!(
+            "{} after writing a backup? type yes to confirm",
+            maintenance.describe()
+        ),
+    };
+    f.render_widget(Paragraph::new(format!("{label}: {}_", prompt.text)), area);
+}
+
+/// The clock's own style, bold while a run is going and blinking while it isn't.
+fn clock_style(timer: &Timer, config: &Config) -> Style {
+    let style = config
+        .theme
+        .clock
+        .style
+        .to_style(config.theme.color_depth());
+    match timer.current_split().is_some() {
+        true => style.bold(),
+        false => style.slow_blink(),
+    }
+}
+
+/// The clock in big digits above the footer, for `theme.clock.big`.
+pub fn render_big_clock<B: Backend>(f: &mut Frame<B>, area: Rect, timer: &Timer, config: &Config) {
+    let text = match config.coarse_running_clock && timer.current_phase().is_running() {
+        true => format_whole_seconds(clock_time(timer)),
+        false => format_short(clock_time(timer), config.rounding),
+    };
+    let style = config
+        .theme
+        .clock
+        .style
+        .to_style(config.theme.color_depth());
+    let lines: Vec<Line> = bigtext::lines(&text, 1)
+        .into_iter()
+        .map(|line| Line::styled(line, style))
+        .collect();
+    f.render_widget(Paragraph::new(lines).alignment(Alignment::Right), area);
+}
+
+/// The whole screen given over to the clock, for intermissions and countdowns.
+pub fn render_big_timer<B: Backend>(f: &mut Frame<B>, area: Rect, timer: &Timer, config: &Config) {
+    let text = match config.coarse_running_clock && timer.current_phase().is_running() {
+        true => format_whole_seconds(clock_time(timer)),
+        false => format_short(clock_time(timer), config.rounding),
+    };
+    let scale = bigtext::fit(&text, area.width as usize, area.height as usize);
+    let lines = bigtext::lines(&text, scale);
+    let top = area.height.saturating_sub(lines.len() as u16) / 2;
+    let lines: Vec<Line> = lines.into_iter().map(Line::from).collect();
+    f.render_widget(
+        Paragraph::new(lines).alignment(Alignment::Center),
+        Rect {
+            y: area.y + top,
+            height: area.height - top,
+            ..area
+        },
+    );
+}
+
+/// The projected delta against each of the configured comparisons, side by side.
+pub fn render_reference_deltas<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    timer: &Timer,
+    config: &Config,
+) {
+    let method = timer.current_timing_method();
+    let snapshot = timer.snapshot();
+    let last = timer.run().segments().last().unwrap();
+    let mut spans = Vec::new();
+    for comparison in &config.reference_deltas {
+        // comparisons this run doesn't have are left out
+        if !timer.run().comparisons().any(|name| name == comparison) {
+            continue;
+        }
+        if !spans.is_empty() {
+            spans.push(Span::raw("  "));
+        }
+        let pace = livesplit::analysis::current_pace::calculate(&snapshot, comparison).0;
+        let final_time = last.comparison(comparison)[method];
+        // the pace is the comparison's own final time until the first split, and
+        // shifting every split time only moves the delta once there's a split to compare
+        let offset = match timer.current_split_index() {
+            Some(i) if i > 0 => config.comparison_offset(comparison),
+            _ => TimeSpan::zero(),
+        };
+        spans.push(match pace.zip(final_time) {
+            Some((pace, final_time)) => {
+                let delta = pace - final_time - offset;
+                Span::styled(
+                    format!("{comparison} {}", format_delta(delta, config.rounding)),
+                    delta_style(delta, config),
+                )
+            }
+            None => Span::raw(format!("{comparison} -")),
+        });
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// What the clock shows: game time once it's in use, otherwise the attempt's duration.
+fn clock_time(timer: &Timer) -> TimeSpan {
+    match timer.snapshot().current_time().game_time {
+        Some(game_time) if timer.is_game_time_initialized() => game_time,
+        _ => timer.current_attempt_duration(),
+    }
+}
+
+pub fn render_footer<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    timer: &Timer,
+    config: &Config,
+    target: Option<TimeSpan>,
+    today: &Day,
+    status: Option<&str>,
+) {
+    let method = timer.current_timing_method();
+    let timer_text = match config.coarse_running_clock && timer.current_phase().is_running() {
+        true => format_whole_seconds(clock_time(timer)),
+        false => format_time(clock_time(timer), config.rounding),
+    };
+    let pace =
+        livesplit::analysis::current_pace::calculate(&timer.snapshot(), timer.current_comparison())
+            .0;
+    let goal = goal_time(timer.run(), method, config.goal_time.as_deref()).map(|goal| {
+        let text = format!("goal {}", format_time(goal, config.rounding));
+        match pace {
+            Some(pace) => Span::styled(text, delta_style(pace - goal, config)),
+            None => Span::raw(text),
+        }
+    });
+    let target = target.map(|target| {
+        let text = format!("target {}", format_time(target, config.rounding));
+        match pace {
+            Some(pace) => {
+                let delta = pace - target;
+                Span::styled(
+                    format!("{text} {}", format_delta(delta, config.rounding)),
+                    delta_style(delta, config),
+                )
+            }
+            None => Span::raw(text),
+        }
+    });
+    let gold = current_gold(timer, config);
+    let loss = biggest_loss(timer, config);
+    let sum_of_best = config.sum_of_best.then(|| {
+        let time = sum_of_best_time(timer.run(), method, config);
+        Span::raw(format!(
+            "sob {}",
+            time.map_or(String::from("-"), |time| format_time(time, config.rounding))
+        ))
+    });
+    let mut info = Vec::new();
+    if !config.theme.clock.big {
+        info.push(Span::styled(timer_text, clock_style(timer, config)));
+    }
+    if timer.is_game_time_paused() {
+        info.extend([Span::raw(" "), Span::from("frozen").reversed()]);
+    }
+    for readout in [goal, target, gold, loss, sum_of_best]
+        .into_iter()
+        .flatten()
+    {
+        info.extend([Span::raw("  "), readout]);
+    }
+    if !info.is_empty() {
+        info.push(Span::raw("  "));
+    }
+    info.push(Span::styled(
+        format!("vs {}", timer.current_comparison()),
+        config.theme.accent(),
+    ));
+    if method == TimingMethod::GameTime {
+        info.push(Span::raw(" (game time)"));
+    }
+    if today.attempts > 0 {
+        let mut text = format!("today: {} attempts", today.attempts);
+        if let Some(best) = today.best(method) {
+            text.push_str(&format!(", best {}", format_time(best, config.rounding)));
+        }
+        info.extend([Span::raw("  "), Span::raw(text)]);
+    }
+    let info = Line::from(info);
+    let footer = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(info.width() as u16), Constraint::Min(0)].as_ref())
+        .split(area);
+    f.render_widget(Paragraph::new(info), footer[0]);
+    if let Some(status) = status {
+        f.render_widget(
+            Paragraph::new(status).alignment(Alignment::Right),
+            footer[1],
+        );
+    }
+}
+
+/// The sum of best, going by the average instead of the gold for the segments in
+/// `unreliable_segments`.
+fn sum_of_best_time(run: &Run, method: TimingMethod, config: &Config) -> Option<TimeSpan> {
+    if config.unreliable_segments.is_empty() {
+        return livesplit::analysis::sum_of_segments::calculate_best(
+            run.segments(),
+            false,
+            false,
+            method,
+        );
+    }
+    let average = livesplit::comparison::average_segments::NAME;
+    run.segments()
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| {
+            let gold = segment.best_segment_time()[method];
+            match config
+                .unreliable_segments
+                .iter()
+                .any(|name| name == segment.name())
+            {
+                true => {
+                    livesplit::analysis::comparison_single_segment_time(run, i, average, method)
+                        .or(gold)
+                }
+                false => gold,
+            }
+        })
+        .try_fold(TimeSpan::zero(), |total, time| Some(total + time?))
+}
+
+/// The best time ever done on the segment being run, styled as a gold while the
+/// live segment time is still under it.
+fn current_gold(timer: &Timer, config: &Config) -> Option<Span<'static>> {
+    if !config.current_gold {
+        return None;
+    }
+    let method = timer.current_timing_method();
+    let index = timer.current_split_index()?;
+    let best = timer.run().segments().get(index)?.best_segment_time()[method]?;
+    let text = format!("gold {}", format_time(best, config.rounding));
+    let live = livesplit::analysis::live_segment_time(&timer.snapshot(), index, method);
+    match live.is_none_or(|live| live < best) {
+        true => Some(Span::styled(
+            text,
+            config.theme.gold.to_style(config.theme.color_depth()),
+        )),
+        false => Some(Span::raw(text)),
+    }
+}
+
+/// The segment of this attempt that lost the most time against the comparison,
+/// counting the one in progress once it's behind.
+fn biggest_loss(timer: &Timer, config: &Config) -> Option<Span<'static>> {
+    if !config.biggest_loss || timer.current_phase() == livesplit::TimerPhase::NotRunning {
+        return None;
+    }
+    let method = timer.current_timing_method();
+    let comparison = timer.current_comparison();
+    let snapshot = timer.snapshot();
+    let current = timer.current_split_index()?;
+    let mut deltas: Vec<(usize, TimeSpan)> = (0..current.min(timer.run().len()))
+        .filter_map(|i| {
+            livesplit::analysis::previous_segment_delta(timer, i, comparison, method)
+                .map(|delta| (i, delta))
+        })
+        .collect();
+    if current < timer.run().len() {
+        let live = livesplit::analysis::live_segment_delta(&snapshot, current, comparison, method);
+        deltas.extend(live.map(|delta| (current, delta)));
+    }
+    let (i, loss) = deltas
+        .into_iter()
+        .filter(|&(_, delta)| delta > TimeSpan::zero())
+        .max_by_key(|&(_, delta)| delta)?;
+    Some(Span::styled(
+        format!(
+            "lost most: {} {}",
+            timer.run().segment(i).name(),
+            format_delta(loss, config.rounding)
+        ),
+        delta_style(loss, config),
+    ))
+}
+
+/// A block with borders in the accent color.
+fn bordered(config: &Config) -> Block<'static> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(config.theme.accent())
+}
+
+/// The configured sections that fit the run.
+fn sections(config: &Config, len: usize) -> impl Iterator<Item = &Section> {
+    config
+        .sections
+        .iter()
+        .filter(move |section| section.start <= section.end && section.end < len)
+}
+
+/// How many rows the table has with the section headers, not counting its own header.
+pub fn table_rows(config: &Config, len: usize) -> usize {
+    len + sections(config, len).count()
+}
+
+/// The table row a segment ends up in once the section headers are added.
+/// Reversed, a segment past the end, like the one after a finished run, has no row
+/// above the top to go to and counts as the last one.
+pub fn table_row(config: &Config, len: usize, segment: usize) -> usize {
+    let sections = sections(config, len);
+    match config.reverse_order {
+        true => {
+            let segment = segment.min(len.saturating_sub(1));
+            len - 1 - segment + sections.filter(|section| section.end >= segment).count()
+        }
+        false => segment + sections.filter(|section| section.start <= segment).count(),
+    }
+}
+
+/// A header row with the section's time and how it compares, live while it's in progress.
+fn section_row<'a>(
+    timer: &Timer,
+    method: TimingMethod,
+    config: &Config,
+    section: &'a Section,
+    live_time: Option<TimeSpan>,
+) -> Row<'a> {
+    let run = timer.run();
+    let comparison = timer.current_comparison();
+    let before = |time: Time| match section.start {
+        0 => Some(TimeSpan::zero()),
+        _ => time[method],
+    };
+    let previous = run.segment(section.start.saturating_sub(1));
+    let last = run.segment(section.end);
+
+    let in_progress = timer.current_phase().is_running() || timer.current_phase().is_paused();
+    let ongoing = timer
+        .current_split_index()
+        .is_some_and(|i| in_progress && section.contains(i));
+    let finished = last.split_time()[method];
+    let end = finished.or(live_time.filter(|_| ongoing));
+    let elapsed = before(previous.split_time())
+        .zip(end)
+        .map(|(start, end)| end - start);
+    let comparison_span = |comparison: &str| {
+        before(previous.comparison(comparison))
+            .zip(last.comparison(comparison)[method])
+            .map(|(start, end)| end - start)
+    };
+    let target = comparison_span(comparison);
+    // the offset cancels out between two comparison times, but not against the start
+    let target = match section.start {
+        0 => target.map(|target| target + config.comparison_offset(comparison)),
+        _ => target,
+    };
+
+    let delta_cell = match (elapsed, target) {
+        (Some(elapsed), Some(target)) if finished.is_some() => {
+            let delta = elapsed - target;
+            Cell::from(format_delta(delta, config.rounding)).style(delta_style(delta, config))
+        }
+        _ => Cell::from(""),
+    };
+    let time = elapsed
+        .or(target)
+        .map_or(String::new(), |time| format_short(time, config.rounding));
+    let target = target.map_or(String::new(), |time| format_short(time, config.rounding));
+    let mut cells = vec![Cell::from(section.name.as_str())];
+    for column in config.table_columns() {
+        cells.push(match column {
+            Column::Delta => delta_cell.clone(),
+            Column::Time => Cell::from(time.clone()),
+            Column::Comparison => Cell::from(target.clone()),
+            Column::Average | Column::Median => Cell::from(
+                comparison_span(column.statistic().unwrap())
+                    .map_or(String::new(), |time| format_short(time, config.rounding)),
+            ),
+        });
+    }
+    Row::new(cells).style(Style::default().add_modifier(Modifier::BOLD))
+}
+
+/// The run's own goal comparison if it has one, otherwise the configured goal time.
+fn goal_time(run: &Run, method: TimingMethod, fallback: Option<&str>) -> Option<TimeSpan> {
+    let goal = livesplit::comparison::goal::NAME;
+    run.custom_comparisons()
+        .iter()
+        .any(|comparison| comparison == goal)
+        .then(|| run.segments().last().unwrap().comparison(goal)[method])
+        .flatten()
+        .or_else(|| fallback.and_then(parse_time))
+}
+
+fn delta_style(delta: TimeSpan, config: &Config) -> Style {
+    if is_tie(delta, config.rounding) {
+        config.theme.tie.to_style(config.theme.color_depth())
+    } else if delta < TimeSpan::zero() {
+        Style::default().fg(Color::Green)
+    } else {
+        Style::default().fg(Color::Red)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(reverse_order: bool) -> Config {
+        Config {
+            reverse_order,
+            sections: vec![
+                Section {
+                    name: String::from("first"),
+                    start: 0,
+                    end: 1,
+                },
+                Section {
+                    name: String::from("second"),
+                    start: 2,
+                    end: 4,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rows_follow_section_headers() {
+        let config = config(false);
+        assert_eq!(table_row(&config, 5, 0), 1);
+        assert_eq!(table_row(&config, 5, 1), 2);
+        assert_eq!(table_row(&config, 5, 2), 4);
+        assert_eq!(table_row(&config, 5, 4), 6);
+    }
+
+    #[test]
+    fn reversed_rows_follow_section_headers() {
+        let config = config(true);
+        assert_eq!(table_row(&config, 5, 4), 1);
+        assert_eq!(table_row(&config, 5, 2), 3);
+        assert_eq!(table_row(&config, 5, 1), 5);
+        assert_eq!(table_row(&config, 5, 0), 6);
+    }
+
+    #[test]
+    fn row_count_includes_section_headers() {
+        for reverse_order in [false, true] {
+            let config = config(reverse_order);
+            assert_eq!(table_rows(&config, 5), 7);
+        }
+        // reversed, the last row is the first segment's
+        let config = config(true);
+        assert_eq!(table_row(&config, 5, 0) + 1, table_rows(&config, 5));
+    }
+
+    #[test]
+    fn reversed_segments_past_the_end_clamp_to_the_last() {
+        let config = config(true);
+        assert_eq!(table_row(&config, 5, 10), table_row(&config, 5, 4));
+        let config = Config {
+            reverse_order: true,
+            ..Default::default()
+        };
+        assert_eq!(table_row(&config, 5, 10), 0);
+    }
+}